@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::AocError;
+use crate::helpers;
+use crate::input::{input_name, InputResolver};
+use crate::solution::Puzzle;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expected {
+    part1: Option<i64>,
+    part2: Option<i64>,
+}
+
+impl Expected {
+    pub fn part(&self, part: u8) -> Option<i64> {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => None,
+        }
+    }
+}
+
+// Known answers, as `[dayNN.<input name>]` tables of `part1`/`part2`
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, BTreeMap<String, Expected>>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers, AocError> {
+        toml::from_str(text).map_err(|e| AocError::InvalidAnswers { message: e.to_string() })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Answers, AocError> {
+        Answers::parse(&helpers::read_to_string(path)?)
+    }
+
+    // every input with answers for this day, the puzzle input always comes first
+    pub fn inputs(&self, day: u8) -> Vec<String> {
+        let puzzle_input = input_name(day, None);
+        let samples = self.days.get(&puzzle_input).into_iter()
+            .flat_map(|inputs| inputs.keys())
+            .filter(|&name| *name != puzzle_input)
+            .cloned();
+        std::iter::once(puzzle_input.clone()).chain(samples).collect()
+    }
+
+    pub fn expected(&self, day: u8, input: &str, part: u8) -> Option<i64> {
+        self.days.get(&input_name(day, None))
+            .and_then(|inputs| inputs.get(input))
+            .and_then(|expected| expected.part(part))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Missing,
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Status::Pass => "pass",
+            Status::Fail => "FAIL",
+            Status::Missing => "missing",
+            Status::Error => "ERROR",
+        };
+        f.pad(status)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub input: String,
+    pub part: u8,
+    pub expected: Option<i64>,
+    pub answer: Result<i64, String>,
+}
+
+impl Check {
+    pub fn status(&self) -> Status {
+        match (&self.answer, self.expected) {
+            (Err(_), _) => Status::Error,
+            (Ok(_), None) => Status::Missing,
+            (Ok(answer), Some(expected)) if *answer == expected => Status::Pass,
+            (Ok(_), Some(_)) => Status::Fail,
+        }
+    }
+}
+
+// Run every part of every puzzle on each of its inputs listed in `answers`.
+// A sample is only run for the parts it has an answer for, only the puzzle input can have missing answers.
+pub fn verify(puzzles: &[&dyn Puzzle], parts: &[u8], answers: &Answers, resolver: &InputResolver) -> Vec<Check> {
+    let mut checks = vec![];
+    for puzzle in puzzles {
+        let day = puzzle.day();
+        let puzzle_input = input_name(day, None);
+        for input in answers.inputs(day) {
+            let parts: Vec<u8> = parts.iter().copied()
+                .filter(|&part| input == puzzle_input || answers.expected(day, &input, part).is_some())
+                .collect();
+            if parts.is_empty() {
+                continue;
+            }
+            let content = resolver.resolve(day, Some(&input))
+                .and_then(|path| Ok(helpers::read_to_string(path)?));
+            for part in parts {
+                let answer = match &content {
+                    Ok(content) => puzzle.run(content, part).map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                checks.push(Check { day, input: input.clone(), part, expected: answers.expected(day, &input, part), answer });
+            }
+        }
+    }
+    checks
+}
+
+pub fn format_table(checks: &[Check]) -> String {
+    let mut table = format!("{:<4} {:<10} {:<4} {:>15} {:>15} {}\n", "Day", "Input", "Part", "Expected", "Answer", "Status");
+    for check in checks {
+        let expected = check.expected.map_or("-".to_string(), |expected| expected.to_string());
+        let answer = check.answer.as_ref().map_or("-".to_string(), |answer| answer.to_string());
+        table.push_str(&format!("{:<4} {:<10} {:<4} {:>15} {:>15} {}\n", format!("{:02}", check.day), check.input, check.part, expected, answer, check.status()));
+    }
+    let count = |status: Status| checks.iter().filter(|check| check.status() == status).count();
+    table.push_str(&format!(
+        "{} passed, {} failed, {} missing, {} errors\n",
+        count(Status::Pass), count(Status::Fail), count(Status::Missing), count(Status::Error)
+    ));
+    // the table only shows the status, list the errors themselves below it
+    for check in checks {
+        if let Err(e) = &check.answer {
+            table.push_str(&format!("\nDay {:02} - {} - part {}: {}\n", check.day, check.input, check.part, e));
+        }
+    }
+    table
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::solution;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("[day07.day07]\npart1 = 1\n\n[day07.sample07]\npart2 = 5905\n").unwrap();
+        assert_eq!(answers.inputs(7), vec!["day07", "sample07"]);
+        assert_eq!(answers.inputs(8), vec!["day08"]);
+        assert_eq!(answers.expected(7, "day07", 1), Some(1));
+        assert_eq!(answers.expected(7, "day07", 2), None);
+        assert_eq!(answers.expected(7, "sample07", 2), Some(5905));
+        assert!(Answers::parse("[day07.day07]\npart_1 = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_builtin_answers() {
+        let resolver = InputResolver::new(None, None);
+        let path = resolver.file_candidates(ANSWERS_FILE).pop().unwrap();
+        let answers = Answers::load(path).unwrap();
+        assert_eq!(answers.expected(1, "day01", 2), Some(53221));
+        assert_eq!(answers.inputs(13), vec!["day13", "sample13", "sample13b", "sample13c"]);
+    }
+
+    #[test]
+    fn test_verify() {
+        let answers = Answers::parse("[day01.day01]\npart1 = 1\n\n[day01.sample01]\npart1 = 142\n\n[day01.sample01z]\npart1 = 0\n").unwrap();
+        let checks = verify(&[solution::get(1).unwrap()], &[1], &answers, &InputResolver::new(None, None));
+        let statuses: Vec<Status> = checks.iter().map(|check| check.status()).collect();
+        assert_eq!(statuses, vec![Status::Fail, Status::Pass, Status::Error]);
+        assert_eq!(checks[0].answer, Ok(55834));
+
+        let table = format_table(&checks);
+        assert!(table.contains("01   sample01   1                142             142 pass"), "{}", table);
+        assert!(table.contains("1 passed, 1 failed, 0 missing, 1 errors\n"), "{}", table);
+
+        // the sample has no part 2 answer, it is skipped rather than run
+        let checks = verify(&[solution::get(1).unwrap()], &[1, 2], &answers, &InputResolver::new(None, None));
+        let runs: Vec<(&str, u8)> = checks.iter().map(|check| (check.input.as_str(), check.part)).collect();
+        assert_eq!(runs, vec![("day01", 1), ("day01", 2), ("sample01", 1), ("sample01z", 1)]);
+        assert_eq!(checks[1].status(), Status::Missing);
+    }
+
+    // the puzzle inputs are checked by the tests of each day, only run the samples here
+    #[test]
+    fn test_verify_builtin_answers() {
+        let resolver = InputResolver::new(None, None);
+        let answers = Answers::load(resolver.file_candidates(ANSWERS_FILE).pop().unwrap()).unwrap();
+        let days: Vec<String> = solution::REGISTRY.iter().map(|puzzle| input_name(puzzle.day(), None)).collect();
+        assert_eq!(answers.days.keys().cloned().collect::<Vec<String>>(), days);
+
+        for puzzle in solution::REGISTRY {
+            let day = puzzle.day();
+            for part in [1, 2] {
+                assert!(answers.expected(day, &input_name(day, None), part).is_some(), "day {:02} has no part {} answer", day, part);
+            }
+            for input in answers.inputs(day).into_iter().skip(1) {
+                let content = helpers::read_to_string(resolver.resolve(day, Some(&input)).unwrap()).unwrap();
+                for part in [1, 2] {
+                    if let Some(expected) = answers.expected(day, &input, part) {
+                        assert_eq!(puzzle.run(&content, part).unwrap(), expected, "day {:02} - {} - part {}", day, input, part);
+                    }
+                }
+            }
+        }
+    }
+}
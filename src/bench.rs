@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AocError;
+use crate::helpers;
+use crate::solution::Puzzle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: f64,
+    pub median: f64,
+    pub p95: f64,
+}
+
+impl Stats {
+    // all values in microseconds
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut micros: Vec<f64> = samples.iter().map(|sample| sample.as_secs_f64() * 1_000_000.0).collect();
+        micros.sort_by(|a, b| a.total_cmp(b));
+        // nearest-rank percentile
+        let percentile = |p: f64| micros.get(((p * micros.len() as f64).ceil() as usize).max(1) - 1).copied().unwrap_or(0.0);
+        Stats { min: percentile(0.0), median: percentile(0.5), p95: percentile(0.95) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: u8,
+    pub runs: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Measurement {
+    pub fn total_median(&self) -> f64 {
+        self.parse.median + self.solve.median
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Baseline {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Baseline, AocError> {
+        let path = path.as_ref();
+        serde_json::from_str(&helpers::read_to_string(path)?)
+            .map_err(|e| AocError::InvalidBaseline { path: path.to_path_buf(), message: e.to_string() })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), AocError> {
+        let json = serde_json::to_string_pretty(self).expect("a baseline is always serializable");
+        Ok(fs::write(path, json)?)
+    }
+
+    pub fn find(&self, day: u8, part: u8) -> Option<&Measurement> {
+        self.measurements.iter().find(|m| m.day == day && m.part == part)
+    }
+}
+
+// Warm up, then time `runs` parses and solves of one part.
+pub fn measure(puzzle: &dyn Puzzle, input: &str, part: u8, warmup: usize, runs: usize) -> Result<Measurement, AocError> {
+    for _ in 0..warmup {
+        puzzle.run_timed(input, part)?;
+    }
+    let mut parse = vec![];
+    let mut solve = vec![];
+    for _ in 0..runs {
+        let timed = puzzle.run_timed(input, part)?;
+        parse.push(timed.parse);
+        solve.push(timed.solve);
+    }
+    Ok(Measurement { day: puzzle.day(), part, runs: parse.len(), parse: Stats::from_samples(&parse), solve: Stats::from_samples(&solve) })
+}
+
+// Relative change of the median parse + solve time, `None` without a baseline for this day and part
+pub fn change(measurement: &Measurement, baseline: &Baseline) -> Option<f64> {
+    baseline.find(measurement.day, measurement.part)
+        .filter(|previous| previous.total_median() > 0.0)
+        .map(|previous| measurement.total_median() / previous.total_median() - 1.0)
+}
+
+pub fn format_table(measurements: &[Measurement], baseline: Option<&Baseline>, threshold: f64) -> String {
+    let mut table = format!(
+        "{:<4} {:<4} {:>10} {:>10} {:>10} {:>12} {:>12} {:>12} {}\n",
+        "Day", "Part", "parse min", "median", "p95", "solve min", "median", "p95", "vs baseline"
+    );
+    for m in measurements {
+        let comparison = match baseline.map(|baseline| change(m, baseline)) {
+            None => String::new(),
+            Some(None) => "new".to_string(),
+            Some(Some(change)) if change > threshold => format!("{:+.1}% SLOWER", change * 100.0),
+            Some(Some(change)) => format!("{:+.1}%", change * 100.0),
+        };
+        let line = format!(
+            "{:<4} {:<4} {:>10.1} {:>10.1} {:>10.1} {:>12.1} {:>12.1} {:>12.1} {}",
+            format!("{:02}", m.day), m.part, m.parse.min, m.parse.median, m.parse.p95, m.solve.min, m.solve.median, m.solve.p95, comparison
+        );
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table.push_str("(times in µs)\n");
+    table
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::solution;
+
+    use super::*;
+
+    fn measurement(day: u8, part: u8, median: f64) -> Measurement {
+        let stats = Stats { min: median, median, p95: median };
+        Measurement { day, part, runs: 1, parse: Stats { min: 0.0, median: 0.0, p95: 0.0 }, solve: stats }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_micros).collect();
+        assert_eq!(Stats::from_samples(&samples), Stats { min: 1.0, median: 10.0, p95: 19.0 });
+        assert_eq!(Stats::from_samples(&[Duration::from_micros(7)]), Stats { min: 7.0, median: 7.0, p95: 7.0 });
+    }
+
+    #[test]
+    fn test_measure() {
+        let sample = helpers::read_to_string("src/input/sample01.txt").unwrap();
+        let m = measure(solution::get(1).unwrap(), &sample, 1, 1, 5).unwrap();
+        assert_eq!((m.day, m.part, m.runs), (1, 1, 5));
+        assert!(m.solve.min <= m.solve.median && m.solve.median <= m.solve.p95);
+    }
+
+    #[test]
+    fn test_compare_with_baseline() {
+        let baseline = Baseline { measurements: vec![measurement(7, 1, 100.0), measurement(7, 2, 100.0)] };
+        assert_eq!(change(&measurement(7, 1, 150.0), &baseline), Some(0.5));
+        assert_eq!(change(&measurement(8, 1, 150.0), &baseline), None);
+
+        let table = format_table(&[measurement(7, 1, 150.0), measurement(7, 2, 105.0), measurement(8, 1, 1.0)], Some(&baseline), 0.1);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[1].ends_with("+50.0% SLOWER"), "{}", table);
+        assert!(lines[2].ends_with(" +5.0%"), "{}", table);
+        assert!(lines[3].ends_with(" new"), "{}", table);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let path = std::env::temp_dir().join(format!("aoc-2023-baseline-{}.json", std::process::id()));
+        let baseline = Baseline { measurements: vec![measurement(3, 2, 12.5)] };
+        baseline.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The states visited by repeatedly applying a step function until one of them comes back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    // number of steps before entering the cycle
+    pub start: usize,
+    pub length: usize,
+    // every state until the first repeat, `states[start + length]` would be `states[start]` again
+    states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    // every state until the first repeat, the ones from `start` on form the cycle
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    // the state after `n` steps
+    pub fn state(&self, n: usize) -> &S {
+        if n < self.states.len() {
+            &self.states[n]
+        } else {
+            &self.states[self.start + (n - self.start) % self.length]
+        }
+    }
+}
+
+// Step from `initial` until a state repeats. Never returns if the states never repeat.
+pub fn find_cycle<S, F>(initial: S, step: F) -> Cycle<S>
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> S {
+    match explore(initial, step, None) {
+        Ok(cycle) => cycle,
+        Err(_) => unreachable!("there is no step limit"),
+    }
+}
+
+// The state after `n` steps, skipping the remaining iterations as soon as a cycle is found
+pub fn nth_state<S, F>(initial: S, step: F, n: usize) -> S
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> S {
+    match explore(initial, step, Some(n)) {
+        Ok(cycle) => cycle.state(n).clone(),
+        Err(state) => state,
+    }
+}
+
+// `Err` with the last state when `limit` steps are done before finding a cycle
+fn explore<S, F>(initial: S, mut step: F, limit: Option<usize>) -> Result<Cycle<S>, S>
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> S {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states: Vec<S> = vec![];
+    let mut state = initial;
+    loop {
+        let steps = states.len();
+        if let Some(&start) = seen.get(&state) {
+            return Ok(Cycle { start, length: steps - start, states });
+        }
+        if limit == Some(steps) {
+            return Err(state);
+        }
+        seen.insert(state.clone(), steps);
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        // 1, 3, 9, 27 % 10 = 7, 21 % 10 = 1
+        let cycle = find_cycle(1, |n| n * 3 % 10);
+        assert_eq!((cycle.start, cycle.length), (0, 4));
+        // 5, 6, 7, 3, 4, 5...
+        let cycle = find_cycle(5, |n| if *n == 7 { 3 } else { n + 1 });
+        assert_eq!((cycle.start, cycle.length), (0, 5));
+        let cycle = find_cycle(0, |n| if *n == 7 { 3 } else { n + 1 });
+        assert_eq!((cycle.start, cycle.length), (3, 5));
+        assert_eq!(*cycle.state(2), 2);
+        assert_eq!(*cycle.state(8), 3);
+        assert_eq!(*cycle.state(1_000_000_000), 5);
+    }
+
+    #[test]
+    fn test_nth_state() {
+        let step = |n: &u64| if *n == 7 { 3 } else { n + 1 };
+        for n in 0..50 {
+            let expected = (0..n).fold(0, |state, _| step(&state));
+            assert_eq!(nth_state(0, step, n), expected);
+        }
+        assert_eq!(nth_state(0, |n| n + 1, 1000), 1000);
+        assert_eq!(nth_state(0, step, 1_000_000_000), 5);
+    }
+}
@@ -1,22 +1,48 @@
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Parsed = Vec<String>;
+
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Trebuchet?!"
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, AocError> {
+        Ok(helpers::lines(input))
+    }
+
+    fn part_1(&self, sample: &Vec<String>) -> Result<i64, AocError> {
+        Ok(sample.iter().map(|line| get_coordinates(line)).sum())
+    }
+
+    fn part_2(&self, sample: &Vec<String>) -> Result<i64, AocError> {
+        Ok(sample.iter().map(|line| get_coordinates_v2(line)).sum())
+    }
+}
 
 // ____________________
 // Part 1
 // ____________________
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day01.txt")
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day01.part_1(&Day01.parse(input)?)
 }
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath);
-    sample.unwrap().iter().map(|line| get_coordinates(line)).reduce(|a, b| a + b).unwrap()
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
 }
 
 fn get_coordinates(amended_coordinates: &str) -> i64 {
-    let first = amended_coordinates.chars().find(|c| { c.is_numeric() });
-    let last = amended_coordinates.chars().rev().find(|c| { c.is_numeric() });
+    let first = amended_coordinates.chars().find(|c| { c.is_ascii_digit() });
+    let last = amended_coordinates.chars().rev().find(|c| { c.is_ascii_digit() });
     if let (Some(f), Some(l)) = (first, last) {
         (f.to_string() + l.to_string().as_str()).parse().unwrap()
     } else {
@@ -28,14 +54,12 @@ fn get_coordinates(amended_coordinates: &str) -> i64 {
 // Part 2
 // ____________________
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day01.txt")
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day01.part_2(&Day01.parse(input)?)
 }
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath);
-    sample.unwrap().iter().map(|line| get_coordinates_v2(line)).reduce(|a, b| a + b).unwrap()
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 fn get_coordinates_v2(amended_coordinates_with_txt: &str) -> i64 {
@@ -52,7 +76,7 @@ fn get_coordinates_v2(amended_coordinates_with_txt: &str) -> i64 {
         .replace("seven", "s7n")
         .replace("eight", "e8t")
         .replace("nine", "n9e");
-    return get_coordinates(amended_coordinates.as_str());
+    get_coordinates(amended_coordinates.as_str())
 }
 
 
@@ -62,7 +86,13 @@ mod tests {
 
     #[test]
     fn test_day1_1_part_1_sample() {
-        let res = read_from("src/input/sample01.txt");
+        let res = read_from("src/input/sample01.txt").unwrap();
+        assert_eq!(res, 142);
+    }
+
+    #[test]
+    fn test_day1_1_solve() {
+        let res = solve("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet").unwrap();
         assert_eq!(res, 142);
     }
 
@@ -88,7 +118,7 @@ mod tests {
 
     #[test]
     fn test_day1_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day01.txt").unwrap();
         assert_eq!(res, 53221);
     }
 }
\ No newline at end of file
@@ -6,24 +6,56 @@ use nom::multi::separated_list0;
 use nom::sequence::{preceded, terminated};
 
 use crate::day02::Balls::{Blue, Green, Red};
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
 
+pub struct Day02;
 
-#[allow(dead_code)]
-pub(crate) fn part_1() -> i64 {
-    read_from("src/input/day02.txt")
+impl Solution for Day02 {
+    type Parsed = Vec<(i64, Vec<Vec<Balls>>)>;
+
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Cube Conundrum"
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        input.lines()
+            .enumerate()
+            .map(|(idx, game)| helpers::parse_all(2, idx, game, parse_game))
+            .collect()
+    }
+
+    fn part_1(&self, games: &Self::Parsed) -> Result<i64, AocError> {
+        let res: i64 = games
+            .iter()
+            .filter(|(_, game_res)| is_valid_game(game_res))
+            .map(|(id, _)| id)
+            .sum();
+        Ok(res)
+    }
+
+    fn part_2(&self, games: &Self::Parsed) -> Result<i64, AocError> {
+        let res: i64 = games
+            .iter()
+            .map(|(_, game_res)| get_min_number_of_cubes(game_res))
+            .map(|(r, g, b)| r * g * b)
+            .sum();
+        Ok(res)
+    }
 }
 
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath);
-    let res: i64 = sample.unwrap()
-        .iter()
-        .map(|game| parse_game(game.as_str()).unwrap().1)
-        .filter(|(_, game_res)| is_valid_game(game_res))
-        .map(|(id, _)| id)
-        .sum();
-    res
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day02.part_1(&Day02.parse(input)?)
+}
+
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
 }
 
 // 12 red cubes, 13 green cubes, and 14 blue cubes
@@ -36,7 +68,7 @@ fn is_valid_game(game_res: &[Vec<Balls>]) -> bool {
 }
 
 #[derive(Debug, PartialEq)]
-enum Balls {
+pub enum Balls {
     Red(i64),
     Green(i64),
     Blue(i64),
@@ -87,15 +119,12 @@ fn parse_game(input: &str) -> IResult<&str, (i64, Vec<Vec<Balls>>)> {
     Ok((input, (game_id, balls)))
 }
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath);
-    let res: i64 = sample.unwrap()
-        .iter()
-        .map(|game| parse_game(game.as_str()).unwrap().1)
-        .map(|(_, game_res)| get_min_number_of_cubes(&game_res))
-        .map(|(r, g, b)| r * g * b)
-        .sum();
-    res
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day02.part_2(&Day02.parse(input)?)
+}
+
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 fn get_min_number_of_cubes(game_res: &[Vec<Balls>]) -> (i64, i64, i64) {
@@ -109,25 +138,19 @@ fn get_min_number_of_cubes(game_res: &[Vec<Balls>]) -> (i64, i64, i64) {
         })
 }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day02.txt")
-}
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_day2_1() {
-        let res = part_1();
+        let res = read_from("src/input/day02.txt").unwrap();
         assert_eq!(res, 2720);
     }
 
     #[test]
     fn test_day2_1_sample() {
-        let res = read_from("src/input/sample02.txt");
+        let res = read_from("src/input/sample02.txt").unwrap();
         assert_eq!(res, 8);
     }
 
@@ -183,44 +206,41 @@ mod tests {
     }
 
     #[test]
-    fn test_day2_is_valid_game() {
-        assert_eq!(
-            is_valid_game(&vec![vec![Red(12)]]),
-            true
-        );
+    fn test_day2_parse_error() {
+        let err = solve("Game 1: 3 blue, 4 red\nGame 2: 1 purple").unwrap_err();
         assert_eq!(
-            is_valid_game(&vec![vec![Red(13)]]),
-            false
-        );
-        assert_eq!(
-            is_valid_game(&vec![vec![Red(12), Green(14)]]),
-            false
-        );
-        assert_eq!(
-            is_valid_game(&vec![vec![Red(13)], vec![Blue(15)]]),
-            false
+            err.to_string(),
+            "day 02: parse error at line 2, column 9: unexpected trailing input\n    Game 2: 1 purple\n            ^"
         );
     }
 
+    #[test]
+    fn test_day2_is_valid_game() {
+        assert!(is_valid_game(&[vec![Red(12)]]));
+        assert!(!is_valid_game(&[vec![Red(13)]]));
+        assert!(!is_valid_game(&[vec![Red(12), Green(14)]]));
+        assert!(!is_valid_game(&[vec![Red(13)], vec![Blue(15)]]));
+    }
+
     #[test]
     fn test_day2_2_sample() {
-        let res = read_from_v2("src/input/sample02.txt");
+        let res = read_from_v2("src/input/sample02.txt").unwrap();
         assert_eq!(res, 2286);
     }
 
     #[test]
     fn test_day2_2_get_min_number_of_cubes() {
-        let res = get_min_number_of_cubes(&vec![vec![Red(1)]]);
+        let res = get_min_number_of_cubes(&[vec![Red(1)]]);
         assert_eq!(res, (1, 0, 0));
         assert_eq!(
-            get_min_number_of_cubes(&vec![vec![Red(2), Green(2)], vec![Red(1), Green(4)], vec![Red(1), Blue(5)]]),
+            get_min_number_of_cubes(&[vec![Red(2), Green(2)], vec![Red(1), Green(4)], vec![Red(1), Blue(5)]]),
             (2, 4, 5));
     }
 
 
     #[test]
     fn test_day2_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day02.txt").unwrap();
         assert_eq!(res, 71535);
     }
 }
\ No newline at end of file
@@ -4,41 +4,85 @@ use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::{Match, Regex};
 
+use crate::error::AocError;
+use crate::grid::Grid;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day03.txt")
-}
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Parsed = Grid<char>;
+
+    fn day(&self) -> u8 {
+        3
+    }
 
+    fn title(&self) -> &'static str {
+        "Gear Ratios"
+    }
 
-fn read_from(filepath: &str) -> i64 {
-    let mut sample = helpers::read(filepath).unwrap();
-    // generate first and last empty lines;
-    let line_length = sample.get(0).unwrap().len();
-    let empty_line = ".".repeat(line_length);
-
-    sample.insert(0, empty_line.clone());
-    sample.push(empty_line);
-    let parts_sum: i64 = sample
-        .iter()
-        .tuple_windows::<(_, _, _)>()
-        .flat_map(|(prev, line, next)| get_parts(prev, line, next))
-        .filter_map(|part_num: &str| {
-            match part_num.parse::<i64>() {
-                Ok(num) => Some(num),
-                Err(_) => None
+    fn parse(&self, input: &str) -> Result<Grid<char>, AocError> {
+        // the parts are found by byte offsets in each line
+        for (idx, line) in input.lines().enumerate() {
+            if let Some(offset) = line.find(|c: char| !c.is_ascii()) {
+                return Err(AocError::parse(3, line, idx + 1, &line[offset..], "the schematic can only contain ASCII characters"));
             }
-        })
-        .sum();
-    parts_sum
+        }
+        let schematic = Grid::parse(3, input, |c| c)?;
+        if schematic.is_empty() {
+            return Err(AocError::unsolvable(3, "the schematic is empty"));
+        }
+        Ok(schematic)
+    }
+
+    fn part_1(&self, schematic: &Grid<char>) -> Result<i64, AocError> {
+        let mut sample = schematic.lines();
+        // generate first and last empty lines;
+        let line_length = sample[0].len();
+        let empty_line = ".".repeat(line_length);
+
+        sample.insert(0, empty_line.clone());
+        sample.push(empty_line);
+        sample
+            .iter()
+            .tuple_windows::<(_, _, _)>()
+            .flat_map(|(prev, line, next)| get_parts(prev, line, next))
+            .map(parse_part_number)
+            .sum()
+    }
+
+    fn part_2(&self, schematic: &Grid<char>) -> Result<i64, AocError> {
+        schematic
+            .lines()
+            .iter()
+            .tuple_windows::<(_, _, _)>()
+            .flat_map(|(prev, line, next)| get_gears(prev, line, next))
+            .map(|(gear_1, gear_2): (&str, &str)| {
+                Ok(parse_part_number(gear_1)? * parse_part_number(gear_2)?)
+            })
+            .sum()
+    }
 }
 
 
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day03.part_1(&Day03.parse(input)?)
+}
+
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
+}
+
+
+fn parse_part_number(part_num: &str) -> Result<i64, AocError> {
+    part_num.parse::<i64>().map_err(|_| AocError::unsolvable(3, format!("part number {} is too large", part_num)))
+}
+
 lazy_static! {
     static ref NUM_REGEX: Regex = Regex::new(r"\d+").unwrap();
 }
-fn parse_line(line: &str) -> (&str, Vec<Match>) {
+fn parse_line(line: &str) -> (&str, Vec<Match<'_>>) {
     let all_match_for_line: Vec<Match> = NUM_REGEX.captures_iter(line).map(|captures| captures.get(0).unwrap()).collect();
     (line, all_match_for_line)
 }
@@ -68,7 +112,7 @@ lazy_static! {
         static ref GEAR: Regex = Regex::new(r"[*]").unwrap();
 }
 
-fn parse_line_v2(line: &str) -> (&str, Vec<Match>) {
+fn parse_line_v2(line: &str) -> (&str, Vec<Match<'_>>) {
     let all_match_for_line: Vec<Match> = GEAR.captures_iter(line).map(|captures| captures.get(0).unwrap()).collect();
     (line, all_match_for_line)
 }
@@ -115,22 +159,12 @@ fn get_gears<'a>(previous: &'a str, line: &'a str, next: &'a str) -> Vec<(&'a st
     res
 }
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let parts_sum: i64 = sample
-        .iter()
-        .tuple_windows::<(_, _, _)>()
-        .flat_map(|(prev, line, next)| get_gears(prev, line, next))
-        .map(|(gear_1, gear_2): (&str, &str)| {
-            gear_1.parse::<i64>().unwrap() * gear_2.parse::<i64>().unwrap()
-        })
-        .sum();
-    parts_sum
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day03.part_2(&Day03.parse(input)?)
 }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day03.txt")
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 
@@ -140,26 +174,45 @@ mod tests {
 
     #[test]
     fn test_day3_1() {
-        let res = part_1();
+        let res = read_from("src/input/day03.txt").unwrap();
         assert_eq!(res, 530495);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample03.txt");
+        let res = read_from("src/input/sample03.txt").unwrap();
         assert_eq!(res, 4361);
     }
 
+    #[test]
+    fn test_uneven_lines() {
+        let err = solve("467..\n...*\n..35.").unwrap_err();
+        assert_eq!(err.to_string(), "day 03: parse error at line 2, column 5: expected 5 columns, found 4\n    ...*\n        ^");
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let err = solve("1é\n..\n").unwrap_err();
+        assert_eq!(err.to_string(), "day 03: parse error at line 1, column 2: the schematic can only contain ASCII characters\n    1é\n     ^");
+        assert!(solve_v2("1é\n..\n").is_err());
+    }
+
+    #[test]
+    fn test_part_number_too_large() {
+        let err = solve("99999999999999999999*").unwrap_err();
+        assert_eq!(err.to_string(), "day 03: cannot solve this input: part number 99999999999999999999 is too large");
+    }
+
     #[test]
     fn test_parse_line() {
         let s = parse_line("467..114..");
         assert_eq!(s.0, "467..114..");
-        assert_eq!(s.1.get(0).unwrap().start(), 0);
-        assert_eq!(s.1.get(0).unwrap().end(), 3);
-        assert_eq!(s.1.get(0).unwrap().as_str(), "467");
-        assert_eq!(s.1.get(1).unwrap().start(), 5);
-        assert_eq!(s.1.get(1).unwrap().end(), 8);
-        assert_eq!(s.1.get(1).unwrap().as_str(), "114");
+        assert_eq!(s.1[0].start(), 0);
+        assert_eq!(s.1[0].end(), 3);
+        assert_eq!(s.1[0].as_str(), "467");
+        assert_eq!(s.1[1].start(), 5);
+        assert_eq!(s.1[1].end(), 8);
+        assert_eq!(s.1[1].as_str(), "114");
     }
 
     #[test]
@@ -181,16 +234,16 @@ mod tests {
     #[test]
     fn test_parse_line_v2() {
         let s = parse_line_v2("..*..*..");
-        assert_eq!(s.1.get(0).unwrap().start(), 2);
-        assert_eq!(s.1.get(0).unwrap().end(), 3);
-        assert_eq!(s.1.get(0).unwrap().as_str(), "*");
-        assert_eq!(s.1.get(1).unwrap().start(), 5);
-        assert_eq!(s.1.get(1).unwrap().end(), 6);
-        assert_eq!(s.1.get(1).unwrap().as_str(), "*");
+        assert_eq!(s.1[0].start(), 2);
+        assert_eq!(s.1[0].end(), 3);
+        assert_eq!(s.1[0].as_str(), "*");
+        assert_eq!(s.1[1].start(), 5);
+        assert_eq!(s.1[1].end(), 6);
+        assert_eq!(s.1[1].as_str(), "*");
         let s1 = parse_line_v2("...*......");
-        assert_eq!(s1.1.get(0).unwrap().start(), 3);
-        assert_eq!(s1.1.get(0).unwrap().end(), 4);
-        assert_eq!(s1.1.get(0).unwrap().as_str(), "*");
+        assert_eq!(s1.1[0].start(), 3);
+        assert_eq!(s1.1[0].end(), 4);
+        assert_eq!(s1.1[0].as_str(), "*");
     }
 
     #[test]
@@ -223,14 +276,14 @@ mod tests {
 
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample03.txt");
+        let res = read_from_v2("src/input/sample03.txt").unwrap();
         assert_eq!(res, 467835);
     }
 
 
     #[test]
     fn test_day3_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day03.txt").unwrap();
         assert_eq!(res, 80253814);
     }
 }
\ No newline at end of file
@@ -1,48 +1,95 @@
 use std::collections::HashSet;
 use nom::{IResult, Parser};
 use nom::bytes::complete::tag;
+use nom::character::complete;
 use nom::character::complete::{digit1, space1};
+use nom::error::{Error, ErrorKind};
 use nom::multi::separated_list0;
 use nom::sequence::{preceded, tuple};
 
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day04.txt")
-}
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Parsed = Vec<Card>;
+
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Scratchcards"
+    }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day04.txt")
+    fn parse(&self, input: &str) -> Result<Vec<Card>, AocError> {
+        input.lines()
+            .enumerate()
+            .map(|(idx, line)| helpers::parse_all(4, idx, line, parse_game))
+            .collect()
+    }
+
+    fn part_1(&self, cards: &Vec<Card>) -> Result<i64, AocError> {
+        let res = cards.iter()
+            .map(get_number_of_winning_num)
+            .map(get_card_value)
+            .sum();
+        Ok(res)
+    }
+
+    fn part_2(&self, cards: &Vec<Card>) -> Result<i64, AocError> {
+        let num_games = cards.len();
+        let mut nb_card: Vec<i64> = vec![1; num_games];
+
+        let res: Vec<usize> = cards.iter()
+            .map(get_number_of_winning_num)
+            .collect();
+
+        for (idx, &range) in res.iter().enumerate() {
+            let value = nb_card[idx];
+            if idx + range >= num_games {
+                return Err(AocError::unsolvable(4, format!("card {} wins copies of cards past the end of the table", idx + 1)));
+            }
+            for unit in nb_card[(idx + 1)..=(idx + range)].iter_mut() {
+                *unit += value;
+            }
+        }
+        let res = nb_card.iter().sum();
+        Ok(res)
+    }
 }
 
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let res = sample.iter()
-        .map(|line| parse_game(line))
-        .map(|game| game.unwrap().1)
-        .map(|res| get_number_of_winning_num(res))
-        .map(get_card_value)
-        .sum();
-    res
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day04.part_1(&Day04.parse(input)?)
 }
 
-fn parse_nums(input: &str) -> IResult<&str, Vec<&str>> {
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
+}
+
+// the winning numbers and the numbers played on a card
+pub type Card = (Vec<i64>, Vec<i64>);
+
+fn parse_nums(input: &str) -> IResult<&str, Vec<i64>> {
     let (input, m) = separated_list0(
-        space1, digit1).parse(input)?;
+        space1, complete::i64).parse(input)?;
     Ok((input, m))
 }
 
-fn parse_game_result(input: &str) -> IResult<&str, (Vec<&str>, Vec<&str>)> {
-    let (input, m) = separated_list0(preceded(tag(" |"), space1), parse_nums).parse(input)?;
-    let winning = m.get(0).unwrap().to_vec();
-    let played = m.get(1).unwrap().to_vec();
+fn parse_game_result(input: &str) -> IResult<&str, Card> {
+    let (input, mut m) = separated_list0(preceded(tag(" |"), space1), parse_nums).parse(input)?;
+    if m.len() != 2 {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::SeparatedList)));
+    }
+    let played = m.pop().unwrap();
+    let winning = m.pop().unwrap();
     Ok((input, (winning, played)))
 }
 
-fn parse_game(input: &str) -> IResult<&str, (Vec<&str>, Vec<&str>)> {
+fn parse_game(input: &str) -> IResult<&str, Card> {
     let (input, m) = preceded(
         tuple((
             tag("Card"),
@@ -56,34 +103,19 @@ fn parse_game(input: &str) -> IResult<&str, (Vec<&str>, Vec<&str>)> {
     Ok((input, m))
 }
 
-fn get_number_of_winning_num((winning_num, played_nums): (Vec<&str>, Vec<&str>)) -> usize {
-    let hash_win: HashSet<&str> = HashSet::from_iter(winning_num.iter().cloned());
-    let hash_pla: HashSet<&str> = HashSet::from_iter(played_nums.iter().cloned());
-    let res = hash_win.intersection(&hash_pla).collect::<Vec<&&str>>().len();
-    res
+fn get_number_of_winning_num((winning_num, played_nums): &Card) -> usize {
+    let hash_win: HashSet<&i64> = HashSet::from_iter(winning_num.iter());
+    let hash_pla: HashSet<&i64> = HashSet::from_iter(played_nums.iter());
+    hash_win.intersection(&hash_pla).count()
 }
 
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let num_games = sample.len();
-    let mut nb_card: Vec<i64> = vec![1; num_games];
-
-    let res: Vec<i64> = sample.iter()
-        .map(|line| parse_game(line))
-        .map(|game| game.unwrap().1)
-        .map(|res| get_number_of_winning_num(res) as i64)
-        .collect();
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day04.part_2(&Day04.parse(input)?)
+}
 
-    for (idx, range) in res.iter().enumerate() {
-        let value = nb_card[idx];
-        let rangeusize = *range as usize;
-        for (_, unit) in nb_card[(idx + 1)..=(idx + rangeusize)].iter_mut().enumerate() {
-            *unit += value;
-        }
-    }
-    let res = nb_card.iter().sum();
-    res
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 fn get_card_value(num_of_wins: usize) -> i64 {
@@ -100,43 +132,50 @@ mod tests {
 
     #[test]
     fn test_day4_1() {
-        let res = part_1();
+        let res = read_from("src/input/day04.txt").unwrap();
         assert_eq!(res, 24175);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample04.txt");
+        let res = read_from("src/input/sample04.txt").unwrap();
         assert_eq!(res, 13);
     }
 
     #[test]
     fn test_parse_nums() {
         let res = parse_nums("12 23 45").unwrap();
-        assert_eq!(res.1, vec!["12", "23", "45"]);
+        assert_eq!(res.1, vec![12, 23, 45]);
         let res_with_double_space = parse_nums("12 23  5").unwrap();
-        assert_eq!(res_with_double_space.1, vec!["12", "23", "5"]);
+        assert_eq!(res_with_double_space.1, vec![12, 23, 5]);
     }
 
     #[test]
     fn test_parse_game_result() {
         let res = parse_game_result("12 23 45 | 12 45 78").unwrap();
-        assert_eq!(res.1, (vec!["12", "23", "45"], vec!["12", "45", "78"]));
+        assert_eq!(res.1, (vec![12, 23, 45], vec![12, 45, 78]));
         let res_space = parse_game_result("2 23 45 |  2 45 78").unwrap();
-        assert_eq!(res_space.1, (vec!["2", "23", "45"], vec!["2", "45", "78"]));
+        assert_eq!(res_space.1, (vec![2, 23, 45], vec![2, 45, 78]));
     }
 
     #[test]
     fn test_parse_game() {
         let res = parse_game("Card  71: 12 23 45 | 12 45 78").unwrap();
-        assert_eq!(res.1, (vec!["12", "23", "45"], vec!["12", "45", "78"]));
+        assert_eq!(res.1, (vec![12, 23, 45], vec![12, 45, 78]));
         let res_space = parse_game("Card  72:  2 23 45 | 12 45 78").unwrap();
-        assert_eq!(res_space.1, (vec!["2", "23", "45"], vec!["12", "45", "78"]));
+        assert_eq!(res_space.1, (vec![2, 23, 45], vec![12, 45, 78]));
+    }
+
+    #[test]
+    fn test_parse_game_without_separator() {
+        assert!(parse_game_result("12 23 45").is_err());
+        let err = Day04.parse("Card 1: 41 48 | 83 86\nCard 2: 13 32 20 16").unwrap_err();
+        assert!(err.to_string().starts_with("day 04: parse error at line 2, column 20"));
     }
 
     #[test]
     fn test_get_number_of_winning_num() {
-        let res = get_number_of_winning_num((vec!["12", "23", "45"], vec!["12", "45", "78"]));
+        let res = get_number_of_winning_num(&(vec![12, 23, 45], vec![12, 45, 78]));
         assert_eq!(res, 2);
     }
 
@@ -151,14 +190,14 @@ mod tests {
 
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample04.txt");
+        let res = read_from_v2("src/input/sample04.txt").unwrap();
         assert_eq!(res, 30);
     }
 
 
     #[test]
     fn test_day4_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day04.txt").unwrap();
         assert_eq!(res, 18846301);
     }
 }
\ No newline at end of file
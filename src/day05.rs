@@ -1,93 +1,109 @@
-use std::fs::read_to_string;
-
 use nom::{IResult, Parser};
 use nom::bytes::complete::tag;
-use nom::character::complete::{i64 as parse_i64, newline};
+use nom::character::complete::{alpha1, i64 as parse_i64, newline};
 use nom::multi::{many0, separated_list0, separated_list1};
-use nom::sequence::{pair, preceded, tuple};
+use nom::sequence::{pair, preceded, separated_pair, terminated, tuple};
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day05.txt")
-}
+use crate::error::AocError;
+use crate::helpers;
+use crate::solution::Solution;
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Parsed = Almanach;
+
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn title(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
+    }
+
+    fn parse(&self, input: &str) -> Result<Almanach, AocError> {
+        parse_maps(input)
+    }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day05.txt")
+    fn part_1(&self, almanach: &Almanach) -> Result<i64, AocError> {
+        almanach.seeds.iter()
+            .map(|&seed| almanach.location(seed))
+            .min()
+            .ok_or_else(|| AocError::unsolvable(5, "the almanac lists no seed"))
+    }
+
+    fn part_2(&self, almanach: &Almanach) -> Result<i64, AocError> {
+        let Some(seed_ranges) = &almanach.seed_ranges else {
+            return Err(unpaired_seed_error(&almanach.seeds));
+        };
+        almanach.location_ranges(seed_ranges.clone()).into_iter()
+            .map(|(start, _)| start)
+            .min()
+            .ok_or_else(|| AocError::unsolvable(5, "the almanac lists no seed range"))
+    }
 }
 
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = read_to_string(filepath).unwrap();
-    let almanach = parse_maps(sample.as_str());
-    let min = almanach.seeds.iter().filter_map(|&seed| {
-        // println!("seeds {:?}", seed);
-        let soil = map_source_to_dest(
-            seed,
-            almanach.sts_map.clone(),
-        );
-        if let Some(soil) = soil {
-            // println!("soil {:?}", soil);
-            let fertilizer = map_source_to_dest(
-                soil,
-                almanach.stf_map.clone(),
-            );
-            if let Some(fertilizer) = fertilizer {
-                // println!("ferr {:?}", fertilizer);
-                let water = map_source_to_dest(
-                    fertilizer,
-                    almanach.ftw_map.clone(),
-                );
-                if let Some(water) = water {
-                    // println!("water {:?}", water);
-                    let light = map_source_to_dest(
-                        water,
-                        almanach.wtl_map.clone(),
-                    );
-                    if let Some(light) = light {
-                        // println!("light {:?}", light);
-                        let temp = map_source_to_dest(
-                            light,
-                            almanach.ltt_map.clone(),
-                        );
-                        if let Some(temp) = temp {
-                            // println!("temp {:?}", temp);
-                            let humidity = map_source_to_dest(
-                                temp,
-                                almanach.tth_map.clone(),
-                            );
-                            if let Some(humidity) = humidity {
-                                // println!("humidi {:?}", humidity);
-                                let location = map_source_to_dest(
-                                    humidity,
-                                    almanach.htl_map.clone(),
-                                );
-                                // println!("locatione {:?}", location);
-                                return location;
-                            }
-                        }
-                    }
-                }
-            }
-        } else {
-            println!("no soil");
-        }
-        None
-    }).min().unwrap_or(0);
-    min
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day05.part_1(&Day05.parse(input)?)
 }
 
-fn map_source_to_dest(id: i64, mapping: Vec<(i64, i64, i64)>) -> Option<i64> {
-    // println!("looking for id:{:?} in {:?}", id, mapping);
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
+}
+
+fn map_source_to_dest(id: i64, mapping: &[(i64, i64, i64)]) -> i64 {
     mapping.iter().find_map(|(destination, source, range)| {
         if (source <= &id) && ((source + range) > id) {
-            let res = destination + (id - source);
-            // println!("mapping:{:?} to {}, because between {} and {}", id, res, source, (source + range));
-            Some(res)
+            Some(destination + (id - source))
         } else {
             None
         }
-    }).or(Some(id))
+    }).unwrap_or(id)
+}
+
+// Every id that `map_source_to_dest` sends to `id`, there can be several of them
+// when a conversion lands on a value that is not converted itself.
+fn map_dest_to_source(id: i64, mapping: &[(i64, i64, i64)]) -> Vec<i64> {
+    let mut sources: Vec<i64> = mapping.iter()
+        .filter(|&&(destination, _, range)| destination <= id && id < destination + range)
+        .map(|&(destination, source, _)| source + (id - destination))
+        .chain(std::iter::once(id))
+        // with overlapping conversions only the first one applies
+        .filter(|&source| map_source_to_dest(source, mapping) == id)
+        .collect();
+    sources.sort();
+    sources.dedup();
+    sources
+}
+
+// Map whole `(start, len)` ranges through a layer, splitting them at the boundaries of its conversion maps.
+// Values outside of every conversion map keep their number.
+fn map_ranges(ranges: Vec<(i64, i64)>, mapping: &[(i64, i64, i64)]) -> Vec<(i64, i64)> {
+    let mut pending: Vec<(i64, i64)> = ranges.into_iter().filter(|&(_, len)| len > 0).collect();
+    let mut mapped = vec![];
+    for &(destination, source, range) in mapping {
+        let source_end = source + range;
+        let mut unmapped = vec![];
+        for (start, len) in pending {
+            let end = start + len;
+            let before_end = end.min(source);
+            if start < before_end {
+                unmapped.push((start, before_end - start));
+            }
+            let (overlap_start, overlap_end) = (start.max(source), end.min(source_end));
+            if overlap_start < overlap_end {
+                mapped.push((destination + (overlap_start - source), overlap_end - overlap_start));
+            }
+            let after_start = start.max(source_end);
+            if after_start < end {
+                unmapped.push((after_start, end - after_start));
+            }
+        }
+        pending = unmapped;
+    }
+    mapped.extend(pending);
+    mapped
 }
 
 fn parse_seeds(input: &str) -> IResult<&str, Vec<i64>> {
@@ -95,197 +111,212 @@ fn parse_seeds(input: &str) -> IResult<&str, Vec<i64>> {
     Ok((input, seeds))
 }
 
+// point at the last seed of the line, the one left without a length
+fn unpaired_seed_error(seeds: &[i64]) -> AocError {
+    let line = format!("seeds: {}", seeds.iter().map(|seed| seed.to_string()).collect::<Vec<_>>().join(" "));
+    let last = line.rfind(' ').map_or(line.len(), |idx| idx + 1);
+    AocError::parse(5, &line, 1, &line[last..], format!("{} seeds cannot be read as start/length pairs", seeds.len()))
+}
+
 fn parse_seeds_v2(input: &str) -> IResult<&str, Vec<(i64, i64)>> {
     let (input, seeds) = preceded(tag("seeds: "), separated_list0(tag(" "), pair(parse_i64, preceded(tag(" "), parse_i64)))).parse(input)?;
     Ok((input, seeds))
 }
 
-fn parse_seed_to_soil_block(input: &str) -> IResult<&str, Vec<(i64, i64, i64)>> {
-    let (input, mapping) = preceded(tag("seed-to-soil map:"), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-    Ok((input, mapping))
+// `<source>-to-<destination> map:` followed by its conversions
+fn parse_layer(input: &str) -> IResult<&str, Layer> {
+    let (input, (source, destination)) = terminated(separated_pair(alpha1, tag("-to-"), alpha1), tag(" map:")).parse(input)?;
+    let (input, conversions) = preceded(newline, separated_list1(newline, parse_conversion_map)).parse(input)?;
+    Ok((input, Layer { source: source.to_string(), destination: destination.to_string(), conversions }))
 }
 
-// fn parse_generic_block<'a>(tag_name: &'a str) -> impl FnMut {
-//     return move |input: &'a str| -> IResult<&'a str, Vec<(&str,&str,&str)>> {
-//         let (input, mapping) = preceded(tag(tag_name), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-//         Ok((input, mapping))
-//     };
-// }
-
-fn parse_soil_to_fertilizer(input: &str) -> IResult<&str, Vec<(i64, i64, i64)>> {
-    let (input, mapping) = preceded(tag("soil-to-fertilizer map:"), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-    Ok((input, mapping))
-}
-
-fn parse_fertilizer_to_water(input: &str) -> IResult<&str, Vec<(i64, i64, i64)>> {
-    let (input, mapping) = preceded(tag("fertilizer-to-water map:"), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-    Ok((input, mapping))
+fn parse_conversion_map(input: &str) -> IResult<&str, (i64, i64, i64)> {
+    let (input, conversion_map) = tuple((parse_i64, preceded(tag(" "), parse_i64), preceded(tag(" "), parse_i64))).parse(input)?;
+    Ok((input, conversion_map))
 }
 
-fn parse_water_to_light(input: &str) -> IResult<&str, Vec<(i64, i64, i64)>> {
-    let (input, mapping) = preceded(tag("water-to-light map:"), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-    Ok((input, mapping))
+// One block of the almanac converting the numbers of a category into the next one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layer {
+    pub source: String,
+    pub destination: String,
+    // (destination, source, range)
+    conversions: Vec<(i64, i64, i64)>,
 }
 
-fn parse_light_to_temperature(input: &str) -> IResult<&str, Vec<(i64, i64, i64)>> {
-    let (input, mapping) = preceded(tag("light-to-temperature map:"), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-    Ok((input, mapping))
-}
+impl Layer {
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
+    }
 
-fn parse_temperature_to_humidity(input: &str) -> IResult<&str, Vec<(i64, i64, i64)>> {
-    let (input, mapping) = preceded(tag("temperature-to-humidity map:"), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-    Ok((input, mapping))
-}
+    pub fn map(&self, id: i64) -> i64 {
+        map_source_to_dest(id, &self.conversions)
+    }
 
-fn parse_humidity_to_location(input: &str) -> IResult<&str, Vec<(i64, i64, i64)>> {
-    let (input, mapping) = preceded(tag("humidity-to-location map:"), preceded(newline, separated_list1(newline, parse_conversion_map))).parse(input)?;
-    Ok((input, mapping))
-}
+    pub fn map_ranges(&self, ranges: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+        map_ranges(ranges, &self.conversions)
+    }
 
-fn parse_conversion_map(input: &str) -> IResult<&str, (i64, i64, i64)> {
-    let (input, conversion_map) = tuple((parse_i64, preceded(tag(" "), parse_i64), preceded(tag(" "), parse_i64))).parse(input)?;
-    Ok((input, conversion_map))
+    // the ids of the source category converted to `id`
+    pub fn unmap(&self, id: i64) -> Vec<i64> {
+        map_dest_to_source(id, &self.conversions)
+    }
 }
 
-struct Almanach {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanach {
     seeds: Vec<i64>,
-    sts_map: Vec<(i64, i64, i64)>,
-    stf_map: Vec<(i64, i64, i64)>,
-    ftw_map: Vec<(i64, i64, i64)>,
-    wtl_map: Vec<(i64, i64, i64)>,
-    ltt_map: Vec<(i64, i64, i64)>,
-    tth_map: Vec<(i64, i64, i64)>,
-    htl_map: Vec<(i64, i64, i64)>,
+    // `None` when the seeds do not pair up into ranges
+    seed_ranges: Option<Vec<(i64, i64)>>,
+    // in order, each layer starts from the destination of the previous one
+    layers: Vec<Layer>,
 }
 
-fn parse_maps(input: &str) -> Almanach {
-    // separated_list0(preceded(tuple(newline, newline)), pa
-    let (input, seeds) = parse_seeds(input).unwrap();
-    let (input, sts_map) = preceded(many0(newline), parse_seed_to_soil_block).parse(input).unwrap();
-    let (input, stf_map) = preceded(many0(newline), parse_soil_to_fertilizer).parse(input).unwrap();
-    let (input, ftw_map) = preceded(many0(newline), parse_fertilizer_to_water).parse(input).unwrap();
-    let (input, wtl_map) = preceded(many0(newline), parse_water_to_light).parse(input).unwrap();
-    let (input, ltt_map) = preceded(many0(newline), parse_light_to_temperature).parse(input).unwrap();
-    let (input, tth_map) = preceded(many0(newline), parse_temperature_to_humidity).parse(input).unwrap();
-    let (_, htl_map) = preceded(many0(newline), parse_humidity_to_location).parse(input).unwrap();
-
-    Almanach {
-        seeds,
-        sts_map,
-        stf_map,
-        ftw_map,
-        wtl_map,
-        ltt_map,
-        tth_map,
-        htl_map,
+impl Almanach {
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    // all the layers composed, from a seed to its location
+    pub fn location(&self, seed: i64) -> i64 {
+        self.layers.iter().fold(seed, |id, layer| layer.map(id))
+    }
+
+    pub fn location_ranges(&self, seed_ranges: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+        self.layers.iter().fold(seed_ranges, |ranges, layer| layer.map_ranges(ranges))
     }
-}
 
-fn parse_maps_v2(input: &str) -> Almanach {
-    // separated_list0(preceded(tuple(newline, newline)), pa
-    let (input, seeds) = parse_seeds_v2(input).unwrap();
-    let (input, sts_map) = preceded(many0(newline), parse_seed_to_soil_block).parse(input).unwrap();
-    let (input, stf_map) = preceded(many0(newline), parse_soil_to_fertilizer).parse(input).unwrap();
-    let (input, ftw_map) = preceded(many0(newline), parse_fertilizer_to_water).parse(input).unwrap();
-    let (input, wtl_map) = preceded(many0(newline), parse_water_to_light).parse(input).unwrap();
-    let (input, ltt_map) = preceded(many0(newline), parse_light_to_temperature).parse(input).unwrap();
-    let (input, tth_map) = preceded(many0(newline), parse_temperature_to_humidity).parse(input).unwrap();
-    let (_, htl_map) = preceded(many0(newline), parse_humidity_to_location).parse(input).unwrap();
-
-    let range_seed = seeds.iter().flat_map(|&(start, range)| {
-        let end_of_range = start + range;
-        let res: Vec<i64> = (start..end_of_range).map(|e| e).collect();
-        res
-    }).collect();
-    Almanach {
-        seeds: range_seed,
-        sts_map,
-        stf_map,
-        ftw_map,
-        wtl_map,
-        ltt_map,
-        tth_map,
-        htl_map,
+    // every seed ending at `location`, sorted
+    pub fn seeds_for_location(&self, location: i64) -> Vec<i64> {
+        let mut seeds = self.layers.iter().rev().fold(vec![location], |ids, layer| {
+            ids.into_iter().flat_map(|id| layer.unmap(id)).collect()
+        });
+        seeds.sort();
+        seeds.dedup();
+        seeds
+    }
+
+    // the lowest location of the seeds `start..start + len`
+    pub fn min_location(&self, start: i64, len: i64) -> Option<i64> {
+        self.location_ranges(vec![(start, len)]).into_iter().map(|(start, _)| start).min()
+    }
+
+    // every category a seed goes through, `[("seed", 79), ("soil", 81), ..., ("location", 82)]`
+    pub fn path(&self, seed: i64) -> Vec<(&str, i64)> {
+        let mut path = vec![(FIRST_CATEGORY, seed)];
+        for layer in &self.layers {
+            let (_, id) = path[path.len() - 1];
+            path.push((layer.destination.as_str(), layer.map(id)));
+        }
+        path
     }
 }
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = read_to_string(filepath).unwrap();
-    let almanach = parse_maps_v2(sample.as_str());
-    let min = almanach.seeds.iter().filter_map(|&seed| {
-        // println!("seeds {:?}", seed);
-        let soil = map_source_to_dest(
-            seed,
-            almanach.sts_map.clone(),
-        );
-        if let Some(soil) = soil {
-            // println!("soil {:?}", soil);
-            let fertilizer = map_source_to_dest(
-                soil,
-                almanach.stf_map.clone(),
-            );
-            if let Some(fertilizer) = fertilizer {
-                // println!("ferr {:?}", fertilizer);
-                let water = map_source_to_dest(
-                    fertilizer,
-                    almanach.ftw_map.clone(),
-                );
-                if let Some(water) = water {
-                    // println!("water {:?}", water);
-                    let light = map_source_to_dest(
-                        water,
-                        almanach.wtl_map.clone(),
-                    );
-                    if let Some(light) = light {
-                        // println!("light {:?}", light);
-                        let temp = map_source_to_dest(
-                            light,
-                            almanach.ltt_map.clone(),
-                        );
-                        if let Some(temp) = temp {
-                            // println!("temp {:?}", temp);
-                            let humidity = map_source_to_dest(
-                                temp,
-                                almanach.tth_map.clone(),
-                            );
-                            if let Some(humidity) = humidity {
-                                // println!("humidi {:?}", humidity);
-                                let location = map_source_to_dest(
-                                    humidity,
-                                    almanach.htl_map.clone(),
-                                );
-                                // println!("locatione {:?}", location);
-                                return location;
-                            }
-                        }
-                    }
-                }
-            }
-        } else {
-            println!("no soil");
+const FIRST_CATEGORY: &str = "seed";
+
+fn parse_maps(text: &str) -> Result<Almanach, AocError> {
+    let to_error = |e| AocError::from_nom(5, text, 1, e);
+    let (ranges_input, seed_ranges) = parse_seeds_v2(text).map_err(to_error)?;
+    let (mut input, seeds) = parse_seeds(text).map_err(to_error)?;
+    // an odd trailing seed is left unparsed by the pairs
+    let seed_ranges = (ranges_input.len() == input.len()).then_some(seed_ranges);
+    let mut layers: Vec<Layer> = vec![];
+    loop {
+        let (block, _): (&str, Vec<char>) = many0(newline).parse(input).map_err(to_error)?;
+        if block.trim().is_empty() {
+            break;
         }
-        None
-    }).min().unwrap_or(0);
-    min
+        let (remaining, layer) = parse_layer(block).map_err(to_error)?;
+        let expected = layers.last().map_or(FIRST_CATEGORY, |previous| previous.destination.as_str());
+        if layer.source != expected {
+            return Err(AocError::parse(5, text, 1, block, format!("expected a map from `{}`, found `{}`", expected, layer.name())));
+        }
+        layers.push(layer);
+        input = remaining;
+    }
+
+    Ok(Almanach {
+        seeds,
+        seed_ranges,
+        layers,
+    })
+}
+
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day05.part_2(&Day05.parse(input)?)
+}
+
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 
 #[cfg(test)]
 mod tests {
+    use lazy_static::lazy_static;
+    use quickcheck::QuickCheck;
+
     use super::*;
 
+    lazy_static! {
+        static ref ALMANACH: Almanach = Day05.parse(&helpers::read_to_string("src/input/day05.txt").unwrap()).unwrap();
+    }
+
     #[test]
     fn test_day5_1() {
-        let res = part_1();
+        let res = read_from("src/input/day05.txt").unwrap();
         assert_eq!(res, 309796150);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample05.txt");
+        let res = read_from("src/input/sample05.txt").unwrap();
         assert_eq!(res, 35);
     }
 
+    #[test]
+    fn test_parse_maps_error() {
+        let err = solve("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n\nsoil-to-fertilizer mapping:\n0 15 37").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 05: parse error at line 6, column 19: unexpected input (Tag parser failed)\n    soil-to-fertilizer mapping:\n                      ^"
+        );
+        let err = solve("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n\nfertilizer-to-water map:\n0 15 37").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 05: parse error at line 6, column 1: expected a map from `soil`, found `fertilizer-to-water`\n    fertilizer-to-water map:\n    ^"
+        );
+    }
+
+    #[test]
+    fn test_unpaired_seed() {
+        let almanach = Day05.parse("seeds: 1 2 3\n\nseed-to-location map:\n10 0 5").unwrap();
+        assert_eq!(Day05.part_1(&almanach).unwrap(), 11);
+        assert_eq!(
+            Day05.part_2(&almanach).unwrap_err().to_string(),
+            "day 05: parse error at line 1, column 12: 3 seeds cannot be read as start/length pairs\n    seeds: 1 2 3\n               ^"
+        );
+    }
+
+    #[test]
+    fn test_layers() {
+        let almanach = Day05.parse("seeds: 1 2\n\nseed-to-soil map:\n10 0 5\n\nsoil-to-dust map:\n0 12 1\n\ndust-to-location map:\n100 0 20").unwrap();
+        let names: Vec<String> = almanach.layers().iter().map(|layer| layer.name()).collect();
+        assert_eq!(names, vec!["seed-to-soil", "soil-to-dust", "dust-to-location"]);
+        assert_eq!(almanach.location(2), 100);
+        assert_eq!(almanach.path(2), vec![("seed", 2), ("soil", 12), ("dust", 0), ("location", 100)]);
+        assert_eq!(Day05.part_1(&almanach).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_path() {
+        let almanach = Day05.parse(&helpers::read_to_string("src/input/sample05.txt").unwrap()).unwrap();
+        let path = almanach.path(79);
+        assert_eq!(path.len(), 8);
+        assert_eq!(path[1], ("soil", 81));
+        assert_eq!(path[7], ("location", 82));
+    }
+
     #[test]
     fn test_parse_seeds() {
         let res = parse_seeds("seeds: 79 14 55 13").unwrap();
@@ -293,11 +324,12 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_seed_to_soil_block() {
-        let res = parse_seed_to_soil_block("seed-to-soil map:
+    fn test_parse_layer() {
+        let res = parse_layer("seed-to-soil map:
 50 98 2
 52 50 48").unwrap();
-        assert_eq!(res.1, vec![(50, 98, 2), (52, 50, 48)]);
+        assert_eq!(res.1.name(), "seed-to-soil");
+        assert_eq!(res.1.conversions, vec![(50, 98, 2), (52, 50, 48)]);
     }
 
     #[test]
@@ -308,8 +340,67 @@ mod tests {
 
     #[test]
     fn test_map_source_to_dest() {
-        let res = map_source_to_dest(16, vec![(50, 15, 2)]);
-        assert_eq!(res, Some(51));
+        let res = map_source_to_dest(16, &[(50, 15, 2)]);
+        assert_eq!(res, 51);
+        assert_eq!(map_source_to_dest(17, &[(50, 15, 2)]), 17);
+    }
+
+    #[test]
+    fn test_map_dest_to_source() {
+        let mapping = [(50, 98, 2), (52, 50, 48)];
+        assert_eq!(map_dest_to_source(51, &mapping), vec![99]);
+        assert_eq!(map_dest_to_source(81, &mapping), vec![79]);
+        assert_eq!(map_dest_to_source(10, &mapping), vec![10]);
+        // 98 goes to 50 but 50 itself is converted, 1 is not converted and 0 goes to 1
+        assert_eq!(map_dest_to_source(50, &[(50, 98, 2)]), vec![50, 98]);
+        assert_eq!(map_dest_to_source(1, &[(1, 0, 1)]), vec![0, 1]);
+        assert_eq!(map_dest_to_source(3, &[(1, 3, 1)]), Vec::<i64>::new());
+        // only the first of two overlapping conversions applies
+        assert_eq!(map_dest_to_source(20, &[(10, 0, 5), (20, 0, 5)]), vec![20]);
+    }
+
+    #[test]
+    fn test_seeds_for_location() {
+        let almanach = Day05.parse(&helpers::read_to_string("src/input/sample05.txt").unwrap()).unwrap();
+        assert_eq!(almanach.seeds_for_location(82), vec![79]);
+        assert_eq!(almanach.seeds_for_location(46), vec![82]);
+        assert_eq!(almanach.min_location(79, 14), Some(46));
+        assert_eq!(almanach.min_location(79, 0), None);
+    }
+
+    #[test]
+    fn test_layer_round_trip() {
+        fn prop(conversions: Vec<(u8, u8, u8)>, id: u8) -> bool {
+            let mapping: Vec<(i64, i64, i64)> = conversions.into_iter().map(|(d, s, r)| (d as i64, s as i64, r as i64)).collect();
+            let id = id as i64;
+            let forward_inverse = map_dest_to_source(id, &mapping).into_iter().all(|source| map_source_to_dest(source, &mapping) == id);
+            let inverse_forward = map_dest_to_source(map_source_to_dest(id, &mapping), &mapping).contains(&id);
+            forward_inverse && inverse_forward
+        }
+        QuickCheck::new().tests(1000).quickcheck(prop as fn(Vec<(u8, u8, u8)>, u8) -> bool);
+    }
+
+    #[test]
+    fn test_almanach_round_trip() {
+        fn prop(id: u32) -> bool {
+            let id = id as i64;
+            let forward_inverse = ALMANACH.seeds_for_location(id).into_iter().all(|seed| ALMANACH.location(seed) == id);
+            let inverse_forward = ALMANACH.seeds_for_location(ALMANACH.location(id)).contains(&id);
+            forward_inverse && inverse_forward
+        }
+        for id in [0, 50716416, 309796150, u32::MAX] {
+            assert!(prop(id), "{}", id);
+        }
+        QuickCheck::new().tests(1000).quickcheck(prop as fn(u32) -> bool);
+    }
+
+    #[test]
+    fn test_min_location() {
+        fn prop(start: u32, len: u16) -> bool {
+            let (start, len) = (start as i64, len as i64);
+            ALMANACH.min_location(start, len) == (start..start + len).map(|seed| ALMANACH.location(seed)).min()
+        }
+        QuickCheck::new().tests(200).quickcheck(prop as fn(u32, u16) -> bool);
     }
 
     #[test]
@@ -321,15 +412,39 @@ mod tests {
 
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample05.txt");
+        let res = read_from_v2("src/input/sample05.txt").unwrap();
         assert_eq!(res, 46);
     }
 
-    // Skip: bruteforce takes a lot of time
-    #[ignore]
+    #[test]
+    fn test_map_ranges() {
+        let mapping = vec![(50, 98, 2), (52, 50, 48)];
+        // 45..55 is split at 50, 60..100 at 98
+        let mut res = map_ranges(vec![(45, 10), (60, 40)], &mapping);
+        res.sort();
+        assert_eq!(res, vec![(45, 5), (50, 2), (52, 5), (62, 38)]);
+        assert_eq!(map_ranges(vec![(0, 0), (10, 5)], &mapping), vec![(10, 5)]);
+    }
+
+    #[test]
+    fn test_location_ranges_match_single_seeds() {
+        let almanach = Day05.parse(&helpers::read_to_string("src/input/sample05.txt").unwrap()).unwrap();
+        let seed_ranges = almanach.seed_ranges.clone().unwrap();
+        let mut from_ranges: Vec<i64> = almanach.location_ranges(seed_ranges.clone()).into_iter()
+            .flat_map(|(start, len)| start..(start + len))
+            .collect();
+        let mut from_seeds: Vec<i64> = seed_ranges.iter()
+            .flat_map(|&(start, len)| start..(start + len))
+            .map(|seed| almanach.location(seed))
+            .collect();
+        from_ranges.sort();
+        from_seeds.sort();
+        assert_eq!(from_ranges, from_seeds);
+    }
+
     #[test]
     fn test_day5_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day05.txt").unwrap();
         assert_eq!(res, 50716416);
     }
 }
\ No newline at end of file
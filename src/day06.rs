@@ -2,32 +2,73 @@ use nom::{IResult, Parser};
 use nom::bytes::complete::tag;
 use nom::character::complete;
 use nom::character::complete::space1;
+use nom::error::{Error, ErrorKind};
 use nom::multi::{many1, separated_list0};
 use nom::sequence::{preceded, terminated};
 
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day06.txt")
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Parsed = Races;
+
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Wait For It"
+    }
+
+    fn parse(&self, input: &str) -> Result<Races, AocError> {
+        let sample = helpers::lines(input);
+        if sample.len() < 2 {
+            return Err(AocError::parse(6, "", sample.len() + 1, "", "expected a Time line followed by a Distance line"));
+        }
+        Ok(Races {
+            durations: helpers::parse_all(6, 0, &sample[0], parse_duration)?,
+            distances: helpers::parse_all(6, 1, &sample[1], parse_distance)?,
+            duration: helpers::parse_all(6, 0, &sample[0], parse_duration_v2)?,
+            distance: helpers::parse_all(6, 1, &sample[1], parse_distance_v2)?,
+        })
+    }
+
+    fn part_1(&self, races: &Races) -> Result<i64, AocError> {
+        let solutions: i64 = races.durations.iter().zip(races.distances.iter()).map(|(&duration, &distance)| {
+            get_nb_solutions(
+                (duration, distance)
+            )
+        }).reduce(|a, b| a * b).unwrap_or(0);
+        Ok(solutions)
+    }
+
+    fn part_2(&self, races: &Races) -> Result<i64, AocError> {
+        let solution: i64 = get_nb_solutions(
+            (races.duration, races.distance)
+        );
+        Ok(solution)
+    }
 }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day06.txt")
+// The two lines of the sheet, read both as separate races and as a single race with the spaces removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Races {
+    durations: Vec<i64>,
+    distances: Vec<i64>,
+    duration: i64,
+    distance: i64,
 }
 
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let (_, durations) = parse_duration(sample.get(0).unwrap()).unwrap();
-    let (_, distances) = parse_distance(sample.get(1).unwrap()).unwrap();
-    let solutions: i64 = durations.iter().zip(distances.iter()).map(|(&duration, &distance)| {
-        get_nb_solutions(
-            (duration, distance)
-        )
-    }).reduce(|a, b| a * b).unwrap_or(0);
-    solutions
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day06.part_1(&Day06.parse(input)?)
+}
+
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
 }
 
 fn parse_duration(input: &str) -> IResult<&str, Vec<i64>> {
@@ -41,25 +82,15 @@ fn parse_distance(input: &str) -> IResult<&str, Vec<i64>> {
 }
 
 fn get_min_press_time((course_duration, best_distance): (i64, i64)) -> i64 {
-    let min_time_pressed = (0..course_duration).find_map(|time_pressed| {
-        let distance = time_pressed * (course_duration - time_pressed);
-        if distance > best_distance {
-            return Some(time_pressed);
-        }
-        None
-    }).unwrap_or(0);
-    min_time_pressed
+    (0..course_duration)
+        .find(|time_pressed| time_pressed * (course_duration - time_pressed) > best_distance)
+        .unwrap_or(0)
 }
 
 fn get_max_press_time((course_duration, best_distance): (i64, i64)) -> i64 {
-    let max_time_pressed = (0..course_duration).rev().find_map(|time_pressed| {
-        let distance = time_pressed * (course_duration - time_pressed);
-        if distance > best_distance {
-            return Some(time_pressed);
-        }
-        None
-    }).unwrap_or(0);
-    max_time_pressed
+    (0..course_duration).rev()
+        .find(|time_pressed| time_pressed * (course_duration - time_pressed) > best_distance)
+        .unwrap_or(0)
 }
 
 fn get_nb_solutions((course_duration, best_distance): (i64, i64)) -> i64 {
@@ -68,28 +99,26 @@ fn get_nb_solutions((course_duration, best_distance): (i64, i64)) -> i64 {
     max_time_pressed - min_time_pressed + 1
 }
 
-fn parse_duration_v2(input: &str) -> IResult<&str, i64> {
-    let (input, durations): (&str, Vec<&str>) = preceded(terminated(tag("Time:"), many1(space1)), separated_list0(many1(space1), complete::digit1)).parse(input)?;
+fn parse_duration_v2(line: &str) -> IResult<&str, i64> {
+    let (input, durations): (&str, Vec<&str>) = preceded(terminated(tag("Time:"), many1(space1)), separated_list0(many1(space1), complete::digit1)).parse(line)?;
     let duration_str = durations.iter().fold("".to_owned(), |a, &b| a.to_owned() + b);
-    let duration = duration_str.parse::<i64>().unwrap();
+    let duration = duration_str.parse::<i64>().map_err(|_| nom::Err::Error(Error::new(line, ErrorKind::Digit)))?;
     Ok((input, duration))
 }
 
-fn parse_distance_v2(input: &str) -> IResult<&str, i64> {
-    let (input, distances): (&str, Vec<&str>) = preceded(terminated(tag("Distance:"), many1(space1)), separated_list0(many1(space1), complete::digit1)).parse(input)?;
+fn parse_distance_v2(line: &str) -> IResult<&str, i64> {
+    let (input, distances): (&str, Vec<&str>) = preceded(terminated(tag("Distance:"), many1(space1)), separated_list0(many1(space1), complete::digit1)).parse(line)?;
     let distance_str = distances.iter().fold("".to_owned(), |a, &b| a.to_owned() + b);
-    let distance = distance_str.parse::<i64>().unwrap();
+    let distance = distance_str.parse::<i64>().map_err(|_| nom::Err::Error(Error::new(line, ErrorKind::Digit)))?;
     Ok((input, distance))
 }
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let (_, duration) = parse_duration_v2(sample.get(0).unwrap()).unwrap();
-    let (_, distance) = parse_distance_v2(sample.get(1).unwrap()).unwrap();
-    let solution: i64 = get_nb_solutions(
-        (duration, distance)
-    );
-    solution
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day06.part_2(&Day06.parse(input)?)
+}
+
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 
@@ -99,13 +128,13 @@ mod tests {
 
     #[test]
     fn test_day6_1() {
-        let res = part_1();
+        let res = read_from("src/input/day06.txt").unwrap();
         assert_eq!(res, 211904);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample06.txt");
+        let res = read_from("src/input/sample06.txt").unwrap();
         assert_eq!(res, 288);
     }
 
@@ -139,6 +168,20 @@ mod tests {
         assert_eq!(res, vec![334, 1135, 1350, 2430]);
     }
 
+    #[test]
+    fn test_missing_distance() {
+        let err = solve("Time:      7  15   30").unwrap_err();
+        assert!(err.to_string().starts_with("day 06: parse error at line 2, column 1: expected a Time line followed by a Distance line"));
+    }
+
+    #[test]
+    fn test_parse_races() {
+        let races = Day06.parse("Time:      7  15   30\nDistance:  9  40  200").unwrap();
+        assert_eq!(races, Races { durations: vec![7, 15, 30], distances: vec![9, 40, 200], duration: 71530, distance: 940200 });
+        let err = Day06.parse("Time:      7  15   30\nDistance:  9  40  x").unwrap_err();
+        assert!(err.to_string().starts_with("day 06: parse error at line 2, column 17: unexpected trailing input"), "{}", err);
+    }
+
     #[test]
     fn test_parse_duration_v2() {
         let (_, res) = parse_duration_v2("Time:      7  15   30").unwrap();
@@ -153,13 +196,13 @@ mod tests {
 
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample06.txt");
+        let res = read_from_v2("src/input/sample06.txt").unwrap();
         assert_eq!(res, 71503);
     }
 
     #[test]
     fn test_day6_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day06.txt").unwrap();
         assert_eq!(res, 43364472);
     }
 }
\ No newline at end of file
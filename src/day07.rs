@@ -1,59 +1,129 @@
-use std::cmp::Ordering;
+use std::fmt;
 
 use itertools::Itertools;
-use nom::Parser;
+use nom::{IResult, Parser};
 use nom::character::complete;
 use nom::character::complete::{alphanumeric1, space1};
-use nom::error::Error;
+use nom::combinator::verify;
 use nom::sequence::{pair, terminated};
 
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day07.txt")
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Parsed = Vec<(String, i64)>;
+
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn title(&self) -> &'static str {
+        "Camel Cards"
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<(String, i64)>, AocError> {
+        helpers::lines(input).iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                let (hand, bid) = helpers::parse_all(7, idx, line, parse_hand)?;
+                Ok((hand.to_string(), bid))
+            })
+            .collect()
+    }
+
+    fn part_1(&self, hands: &Vec<(String, i64)>) -> Result<i64, AocError> {
+        total_winnings(hands, Rules::Standard)
+    }
+
+    fn part_2(&self, hands: &Vec<(String, i64)>) -> Result<i64, AocError> {
+        total_winnings(hands, Rules::Jokers)
+    }
+
+    fn explain(&self, hands: &Vec<(String, i64)>, part: u8) -> Option<String> {
+        let rules = if part == 1 { Rules::Standard } else { Rules::Jokers };
+        explain(hands, rules).ok()
+    }
 }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day07.txt")
+
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day07.part_1(&Day07.parse(input)?)
 }
 
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
+}
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let ordered: Vec<(&str, i64, Strength)> = sample.iter().map(|line| parse_hand(line)).map(get_strength).sorted_by(|a: &(&str, i64, Strength), b: &(&str, i64, Strength)| {
-        let ordering = a.2.cmp(&b.2);
-        if ordering == Ordering::Equal {
-            a.0.to_string().chars().enumerate().find_map(|(idx, card)| {
-                let binding = b.0.chars().nth(idx).unwrap().to_string();
-                let c2: &str = binding.as_str();
-                let c2o = ORDER.iter().find_position(|&&o| o == c2).map(|(a, _)| a).unwrap();
-                let card2o = ORDER.iter().find_position(|&&o| o == card.to_string().as_str()).map(|(a, _)| a).unwrap();
-                if c2o == card2o {
-                    None
-                } else {
-                    let order = c2o.cmp(&card2o);
-                    Some(order)
-                }
-            }).unwrap_or(Ordering::Equal)
-        } else {
-            ordering
-        }
-    }).collect();
+// A hand with its place in the ranking, rank 1 is the weakest hand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranked<'a> {
+    pub rank: usize,
+    pub label: &'a str,
+    pub hand: Hand,
+    pub bid: i64,
+}
+
+impl Ranked<'_> {
+    pub fn winnings(&self) -> i64 {
+        self.rank as i64 * self.bid
+    }
+}
+
+pub fn rank_hands(hands: &[(String, i64)], rules: Rules) -> Result<Vec<Ranked<'_>>, AocError> {
+    let hands: Vec<(&str, Hand, i64)> = hands.iter()
+        .map(|(label, bid)| match Hand::new(label, rules) {
+            Some(hand) => Ok((label.as_str(), hand, *bid)),
+            None => Err(AocError::unsolvable(7, format!("`{}` is not a hand of five cards", label))),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(hands.into_iter()
+        .sorted_by(|(_, a, _), (_, b, _)| a.cmp(b))
+        .enumerate()
+        .map(|(idx, (label, hand, bid))| Ranked { rank: idx + 1, label, hand, bid })
+        .collect())
+}
+
+fn total_winnings(hands: &[(String, i64)], rules: Rules) -> Result<i64, AocError> {
+    Ok(rank_hands(hands, rules)?.iter().map(Ranked::winnings).sum())
+}
+
+// why `hand` ranks right above `previous`
+fn tie_break(hand: &Hand, previous: &Hand) -> String {
+    if hand.strength() != previous.strength() {
+        return format!("{} > {}", hand.strength(), previous.strength());
+    }
+    match hand.cards().iter().zip(previous.cards()).enumerate().find(|(_, (a, b))| a != b) {
+        Some((idx, (a, b))) => format!("card {}: {} > {}", idx + 1, a.label(), b.label()),
+        None => "same cards".to_string(),
+    }
+}
 
-    let init = 0i64;
-    let res = ordered.iter().enumerate().fold(init, |a, (idx, (_, bid, _))| a + bid * (idx as i64 + 1));
-    res
+// The ranking as a table, from the weakest hand, with the total winnings
+pub fn explain(hands: &[(String, i64)], rules: Rules) -> Result<String, AocError> {
+    let ranked = rank_hands(hands, rules)?;
+    let mut table = format!("{:>5} {:<5} {:<7} {:<15} {:<32} {:>6} {:>10}\n", "Rank", "Hand", "Played", "Strength", "vs previous", "Bid", "Winnings");
+    for (idx, r) in ranked.iter().enumerate() {
+        let played = if rules.wildcards().is_empty() { "-".to_string() } else { substitute(r.label, rules.wildcards()) };
+        let versus = if idx == 0 { "-".to_string() } else { tie_break(&r.hand, &ranked[idx - 1].hand) };
+        table.push_str(&format!(
+            "{:>5} {:<5} {:<7} {:<15} {:<32} {:>6} {:>10}\n",
+            r.rank, r.label, played, r.hand.strength().to_string(), versus, r.bid, r.winnings()
+        ));
+    }
+    table.push_str(&format!("Total winnings: {}\n", ranked.iter().map(Ranked::winnings).sum::<i64>()));
+    Ok(table)
 }
 
-fn parse_hand(input: &str) -> (&str, i64) {
-    let (_, res): (&str, (&str, i64)) = pair(terminated(alphanumeric1::<&str, Error<&str>>, space1), complete::i64).parse(input).unwrap();
-    res
+fn parse_hand(input: &str) -> IResult<&str, (&str, i64)> {
+    let is_hand = |hand: &str| hand.len() == 5 && hand.chars().all(|card| ORDER.contains(&card));
+    pair(terminated(verify(alphanumeric1, is_hand), space1), complete::i64).parse(input)
 }
 
-#[derive(PartialOrd, Ord, PartialEq, Eq, Debug)]
-enum Strength {
+#[derive(PartialOrd, Ord, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Strength {
     High = 1,
     Pair = 2,
     TwoPair = 3,
@@ -64,260 +134,382 @@ enum Strength {
 }
 
 
-fn get_strength((hand, bid): (&str, i64)) -> (&str, i64, Strength) {
-    let cards: Vec<(char, i64)> = hand.to_string().chars().into_group_map_by(|&card| card).into_iter().map(|(key, group)| (key, group.len() as i64)).collect();
-    let cards_value: Vec<(char, i64)> = cards.iter().sorted_by(|(_, count_a), (_, count_b)| count_b.cmp(count_a)).copied().collect();
-
-    let mut strength = match cards_value.get(0).unwrap().1 {
-        5 => Strength::Five,
-        4 => Strength::Four,
-        3 => Strength::Three,
-        2 => Strength::Pair,
-        _ => Strength::High,
-    };
+impl fmt::Display for Strength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Strength::High => "High card",
+            Strength::Pair => "One pair",
+            Strength::TwoPair => "Two pair",
+            Strength::Three => "Three of a kind",
+            Strength::Full => "Full house",
+            Strength::Four => "Four of a kind",
+            Strength::Five => "Five of a kind",
+        };
+        f.pad(name)
+    }
+}
 
-    if strength == Strength::Three && cards_value.get(1).unwrap().1 == 2 {
-        strength = Strength::Full;
-    } else if strength == Strength::Pair && cards_value.get(1).unwrap().1 == 2 {
-        strength = Strength::TwoPair;
+impl Strength {
+    // from the sizes of the two largest groups of identical cards
+    fn from_groups(largest: usize, second: usize) -> Strength {
+        match (largest, second) {
+            (5, _) => Strength::Five,
+            (4, _) => Strength::Four,
+            (3, 2) => Strength::Full,
+            (3, _) => Strength::Three,
+            (2, 2) => Strength::TwoPair,
+            (2, _) => Strength::Pair,
+            _ => Strength::High,
+        }
     }
-    (hand, bid, strength)
 }
 
-fn get_strength_v2((hand, bid): (&str, i64)) -> (&str, i64, Strength) {
-    let joker: i64 = (*hand).to_string().chars().filter(|&c| c == 'J').count() as i64;
-    let cards: Vec<(char, i64)> = hand.to_string().chars().into_group_map_by(|&card| card).into_iter().map(|(key, group)| (key, group.len() as i64)).collect();
-    let cards_value: Vec<(char, i64)> = cards.iter().sorted_by(|(_, count_a), (_, count_b)| count_b.cmp(count_a)).copied().collect();
-
-    let (card, base_strength) = cards_value.first().unwrap();
-    let strength = match base_strength {
-        5 => Strength::Five,
-        4 => Strength::Four,
-        3 => Strength::Three,
-        2 => Strength::Pair,
-        _ => Strength::High,
-    };
-
-    let with_joker = match strength {
-        Strength::Five => Strength::Five,
-        Strength::Four => {
-            if *card == 'J' || joker == 1 {
-                Strength::Five
-            } else {
-                Strength::Four
-            }
+// how many times each card appears, most frequent first, ties in order of first appearance
+fn count_cards(cards: impl Iterator<Item = char>) -> Vec<(char, usize)> {
+    let mut counts: Vec<(char, usize)> = vec![];
+    for card in cards {
+        match counts.iter_mut().find(|(label, _)| *label == card) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((card, 1)),
         }
-        Strength::Three => {
-            if *card == 'J' {
-                if cards_value.get(1).unwrap().1 == 2 {
-                    Strength::Five
-                } else {
-                    Strength::Four
-                }
-            } else if joker == 2 {
-                Strength::Five
-            } else if joker == 1 {
-                Strength::Four
-            } else if cards_value.get(1).unwrap().1 == 2 {
-                Strength::Full
-            } else {
-                Strength::Three
-            }
+    }
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    counts
+}
+
+// The hand once every wildcard is replaced by the most frequent other card, which always gives the
+// strongest hand: adding the wildcards to the largest group beats splitting them or starting a new group.
+pub fn substitute(hand: &str, wildcards: &[char]) -> String {
+    let target = count_cards(hand.chars().filter(|card| !wildcards.contains(card))).first()
+        .map(|&(card, _)| card)
+        // only wildcards, any card makes five of a kind
+        .or(hand.chars().next());
+    match target {
+        Some(target) => hand.chars().map(|card| if wildcards.contains(&card) { target } else { card }).collect(),
+        None => hand.to_string(),
+    }
+}
+
+// strength of a hand where the `wildcards` act like whatever card makes it strongest
+pub fn classify(hand: &str, wildcards: &[char]) -> Strength {
+    let counts = count_cards(substitute(hand, wildcards).chars());
+    let group = |idx: usize| counts.get(idx).map_or(0, |&(_, count)| count);
+    Strength::from_groups(group(0), group(1))
+}
+
+const ORDER: [char; 13] = ['A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2'];
+const ORDER_2: [char; 13] = ['A', 'K', 'Q', 'T', '9', '8', '7', '6', '5', '4', '3', '2', 'J'];
+
+// How the cards of a hand are valued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rules {
+    Standard,
+    // `J` cards are jokers: the weakest card, but they act like whatever card makes the hand strongest
+    Jokers,
+}
+
+impl Rules {
+    // strongest card first
+    fn order(self) -> &'static [char; 13] {
+        match self {
+            Rules::Standard => &ORDER,
+            Rules::Jokers => &ORDER_2,
         }
-        Strength::Pair => {
-            if *card == 'J' {
-                if cards_value.get(1).unwrap().1 == 2 {
-                    Strength::Four
-                } else {
-                    Strength::Three
-                }
-            } else if joker == 2 {
-                Strength::Four
-            } else if joker == 1 {
-                if cards_value.get(1).unwrap().1 == 2 {
-                    Strength::Full
-                } else {
-                    Strength::Three
-                }
-            } else if cards_value.get(1).unwrap().1 == 2 {
-                Strength::TwoPair
-            } else {
-                Strength::Pair
-            }
+    }
+
+    pub fn wildcards(self) -> &'static [char] {
+        match self {
+            Rules::Standard => &[],
+            Rules::Jokers => &['J'],
         }
-        Strength::High => if joker == 1 { Strength::Pair } else { Strength::High }
-        e => e
-    };
-    (hand, bid, with_joker)
+    }
+
+    fn strength(self, hand: &str) -> Strength {
+        classify(hand, self.wildcards())
+    }
 }
 
-const ORDER: [&str; 13] = ["A", "K", "Q", "J", "T", "9", "8", "7", "6", "5", "4", "3", "2"];
-const ORDER_2: [&str; 13] = ["A", "K", "Q", "T", "9", "8", "7", "6", "5", "4", "3", "2", "J"];
+// `value` is the position of the card in the order of the rules, from 0 for the weakest one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Card {
+    value: u8,
+    label: char,
+}
 
-// FIXME: unused
-type Hand<'a> = (&'a str, i64, Strength);
+impl Card {
+    pub fn new(label: char, rules: Rules) -> Option<Card> {
+        let order = rules.order();
+        order.iter()
+            .position(|&card| card == label)
+            .map(|position| Card { value: (order.len() - 1 - position) as u8, label })
+    }
 
-// FIXME: unused
-trait ByStrength {
-    fn sort_by_strength(&self, comp: Hand) -> Ordering;
+    pub fn label(&self) -> char {
+        self.label
+    }
 }
 
-// FIXME: unused
-impl ByStrength for Hand<'_> {
-    fn sort_by_strength(&self, hand: Hand<'_>) -> Ordering {
-        let ordering = self.2.cmp(&hand.2);
-        if ordering == Ordering::Equal {
-            self.0.to_string().chars().enumerate().find_map(|(idx, card)| {
-                let binding = hand.0.chars().nth(idx).unwrap().to_string();
-                let c2: &str = binding.as_str();
-                let c2o = ORDER.iter().find_position(|&&o| o == c2).map(|(a, _)| a).unwrap();
-                let card2o = ORDER.iter().find_position(|&&o| o == card.to_string().as_str()).map(|(a, _)| a).unwrap();
-                if c2o == card2o {
-                    None
-                } else {
-                    let order = c2o.cmp(&card2o);
-                    Some(order)
-                }
-            }).unwrap_or(Ordering::Equal)
-        } else {
-            ordering
-        }
+// Hands compare by strength first, then card by card from the first one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hand {
+    strength: Strength,
+    cards: [Card; 5],
+}
+
+impl Hand {
+    pub fn new(hand: &str, rules: Rules) -> Option<Hand> {
+        let cards: Vec<Card> = hand.chars().map(|label| Card::new(label, rules)).collect::<Option<_>>()?;
+        Some(Hand { strength: rules.strength(hand), cards: cards.try_into().ok()? })
+    }
+
+    pub fn strength(&self) -> Strength {
+        self.strength
+    }
+
+    pub fn cards(&self) -> &[Card; 5] {
+        &self.cards
     }
 }
 
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let ordered: Vec<(&str, i64, Strength)> = sample.iter().map(|line| parse_hand(line)).map(get_strength_v2).sorted_by(|a: &(&str, i64, Strength), b: &(&str, i64, Strength)| {
-        let ordering = a.2.cmp(&b.2);
-        if ordering == Ordering::Equal {
-            a.0.to_string().chars().enumerate().find_map(|(idx, card)| {
-                let binding = b.0.chars().nth(idx).unwrap().to_string();
-                let c2: &str = binding.as_str();
-                let c2o = ORDER_2.iter().find_position(|&&o| o == c2).map(|(a, _)| a).unwrap();
-                let card2o = ORDER_2.iter().find_position(|&&o| o == card.to_string().as_str()).map(|(a, _)| a).unwrap();
-                if c2o == card2o {
-                    None
-                } else {
-                    let order = c2o.cmp(&card2o);
-                    Some(order)
-                }
-            }).unwrap_or(Ordering::Equal)
-        } else {
-            ordering
-        }
-    }).collect();
-
-    let res = ordered.iter().enumerate().fold(0i64, |a, (idx, (_, bid, _))| {
-        let deref_bid = *bid;
-        let total_bid = deref_bid * (idx as i64 + 1);
-        a + total_bid
-    });
-    res
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day07.part_2(&Day07.parse(input)?)
+}
+
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 
 #[cfg(test)]
 mod tests {
+    use std::cmp::Ordering;
+
     use super::*;
 
     #[test]
     fn test_part_1() {
-        let res = part_1();
+        let res = read_from("src/input/day07.txt").unwrap();
         assert_eq!(res, 251106089);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample07.txt");
+        let res = read_from("src/input/sample07.txt").unwrap();
         assert_eq!(res, 6440);
     }
 
     #[test]
     fn test_read_fromd() {
-        let res = read_from("src/input/sample07d.txt");
+        let res = read_from("src/input/sample07d.txt").unwrap();
         assert_eq!(res, 3542);
     }
 
     #[test]
     fn test_get_strength() {
-        let res = get_strength(("KK677", 28));
-        assert_eq!(res.2, Strength::TwoPair);
-        assert_eq!(get_strength(("KKK77", 28)).2, Strength::Full);
-        assert_eq!(get_strength(("KKKK7", 28)).2, Strength::Four);
-        assert_eq!(get_strength(("32T3K", 28)).2, Strength::Pair);
-        assert_eq!(get_strength(("T55J5", 28)).2, Strength::Three);
-        assert_eq!(get_strength(("KTJJT", 28)).2, Strength::TwoPair);
+        let res = Rules::Standard.strength("KK677");
+        assert_eq!(res, Strength::TwoPair);
+        assert_eq!(Rules::Standard.strength("KKK77"), Strength::Full);
+        assert_eq!(Rules::Standard.strength("KKKK7"), Strength::Four);
+        assert_eq!(Rules::Standard.strength("32T3K"), Strength::Pair);
+        assert_eq!(Rules::Standard.strength("T55J5"), Strength::Three);
+        assert_eq!(Rules::Standard.strength("KTJJT"), Strength::TwoPair);
     }
 
     #[test]
     fn test_get_strength_v2() {
-        let res = get_strength_v2(("32T3K", 28));
-        assert_eq!(res.2, Strength::Pair);
-        assert_eq!(get_strength_v2(("KK677", 28)).2, Strength::TwoPair);
-        assert_eq!(get_strength_v2(("T55J5", 28)).2, Strength::Four);
-        assert_eq!(get_strength_v2(("KTJJT", 28)).2, Strength::Four);
+        let res = Rules::Jokers.strength("32T3K");
+        assert_eq!(res, Strength::Pair);
+        assert_eq!(Rules::Jokers.strength("KK677"), Strength::TwoPair);
+        assert_eq!(Rules::Jokers.strength("T55J5"), Strength::Four);
+        assert_eq!(Rules::Jokers.strength("KTJJT"), Strength::Four);
         // ====
-        assert_eq!(get_strength_v2(("AKJTT", 28)).2, Strength::Three);
-        assert_eq!(get_strength_v2(("AJJTT", 28)).2, Strength::Four);
-        assert_eq!(get_strength_v2(("JJJTT", 28)).2, Strength::Five);
+        assert_eq!(Rules::Jokers.strength("AKJTT"), Strength::Three);
+        assert_eq!(Rules::Jokers.strength("AJJTT"), Strength::Four);
+        assert_eq!(Rules::Jokers.strength("JJJTT"), Strength::Five);
         // ====
-        assert_eq!(get_strength_v2(("JJJJK", 28)).2, Strength::Five);
-        assert_eq!(get_strength_v2(("JJJAK", 28)).2, Strength::Four);
-        assert_eq!(get_strength_v2(("JJJKK", 28)).2, Strength::Five);
-        assert_eq!(get_strength_v2(("JJAAK", 28)).2, Strength::Four);
-        assert_eq!(get_strength_v2(("JAAAK", 28)).2, Strength::Four);
-        assert_eq!(get_strength_v2(("JJAQK", 28)).2, Strength::Three);
+        assert_eq!(Rules::Jokers.strength("JJJJK"), Strength::Five);
+        assert_eq!(Rules::Jokers.strength("JJJAK"), Strength::Four);
+        assert_eq!(Rules::Jokers.strength("JJJKK"), Strength::Five);
+        assert_eq!(Rules::Jokers.strength("JJAAK"), Strength::Four);
+        assert_eq!(Rules::Jokers.strength("JAAAK"), Strength::Four);
+        assert_eq!(Rules::Jokers.strength("JJAQK"), Strength::Three);
         // ====
-        assert_eq!(get_strength_v2(("J345A", 3)).2, Strength::Pair);
-        assert_eq!(get_strength_v2(("2345J", 5)).2, Strength::Pair);
-        assert_eq!(get_strength_v2(("JJJJ2", 3)).2, Strength::Five);
-        assert_eq!(get_strength_v2(("2JJJJ", 3)).2, Strength::Five);
+        assert_eq!(Rules::Jokers.strength("J345A"), Strength::Pair);
+        assert_eq!(Rules::Jokers.strength("2345J"), Strength::Pair);
+        assert_eq!(Rules::Jokers.strength("JJJJ2"), Strength::Five);
+        assert_eq!(Rules::Jokers.strength("2JJJJ"), Strength::Five);
+    }
+
+    // strongest hand over every way to replace the wildcards by any card
+    fn brute_force_classify(hand: &str, wildcards: &[char]) -> Strength {
+        let fixed: String = hand.chars().filter(|card| !wildcards.contains(card)).collect();
+        let wild = hand.len() - fixed.len();
+        // the order of the cards does not change the strength, trying every multiset of replacements is enough
+        ORDER.iter()
+            .combinations_with_replacement(wild)
+            .map(|replacements| strength_of(fixed.chars().chain(replacements.into_iter().copied())))
+            .max()
+            .unwrap()
     }
 
+    // the strength of a hand without wildcards from the sorted sizes of its groups of cards
+    fn strength_of(cards: impl Iterator<Item = char>) -> Strength {
+        let groups: Vec<usize> = cards.counts().into_values().sorted().rev().collect();
+        match groups.as_slice() {
+            [5] => Strength::Five,
+            [4, 1] => Strength::Four,
+            [3, 2] => Strength::Full,
+            [3, 1, 1] => Strength::Three,
+            [2, 2, 1] => Strength::TwoPair,
+            [2, 1, 1, 1] => Strength::Pair,
+            [1, 1, 1, 1, 1] => Strength::High,
+            _ => panic!("{:?} are not the groups of a hand of 5 cards", groups),
+        }
+    }
+
+    // every multiset of 5 cards, each hand once whatever the order of its cards
+    fn all_hands() -> Vec<String> {
+        let hands: Vec<String> = ORDER.iter().combinations_with_replacement(5).map(|cards| cards.into_iter().collect()).collect();
+        assert_eq!(hands.len(), 6188);
+        hands
+    }
+
+    fn check_against_brute_force(wildcard_sets: &[(Vec<char>, usize)]) {
+        let hands = all_hands();
+        for (wildcards, step) in wildcard_sets {
+            for hand in hands.iter().step_by(*step) {
+                assert_eq!(classify(hand, wildcards), brute_force_classify(hand, wildcards), "{} with {:?}", hand, wildcards);
+            }
+        }
+    }
+
+    // every hand for the rules of the puzzle, an evenly spread sample for the other wildcard sets
     #[test]
-    fn test_sort_by_strength() {
-        let hand: Hand = ("KK677", 28, Strength::TwoPair);
-        let res = hand.sort_by_strength(("KK677", 28, Strength::TwoPair));
+    fn test_classify_against_brute_force() {
+        check_against_brute_force(&[(vec![], 1), (vec!['J'], 1), (vec!['2'], 17), (vec!['J', 'A'], 17), (vec!['K', 'Q', 'T'], 17)]);
+    }
+
+    // about ten seconds in debug, run with `cargo test -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_classify_against_brute_force_exhaustive() {
+        check_against_brute_force(&[(vec![], 1), (vec!['J'], 1), (vec!['2'], 1), (vec!['J', 'A'], 1), (vec!['K', 'Q', 'T'], 1)]);
+    }
+
+    #[test]
+    fn test_classify_ignores_card_order() {
+        for hand in ["KTJJT", "J345A", "2JJJJ", "QQJ2Q"] {
+            for permutation in hand.chars().permutations(5) {
+                let permutation: String = permutation.into_iter().collect();
+                assert_eq!(classify(&permutation, &['J']), classify(hand, &['J']), "{}", permutation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_substitute() {
+        assert_eq!(substitute("KTJJT", &['J']), "KTTTT");
+        assert_eq!(substitute("JJJJJ", &['J']), "JJJJJ");
+        assert_eq!(substitute("J2J22", &['J', '2']), "JJJJJ");
+        assert_eq!(substitute("QQ234", &[]), "QQ234");
+        assert_eq!(substitute("2345J", &['J']), "23452");
+    }
+
+    #[test]
+    fn test_rank_hands() {
+        let hands = Day07.parse(&helpers::read_to_string("src/input/sample07.txt").unwrap()).unwrap();
+        let ranked = rank_hands(&hands, Rules::Jokers).unwrap();
+        let labels: Vec<&str> = ranked.iter().map(|r| r.label).collect();
+        assert_eq!(labels, vec!["32T3K", "KK677", "T55J5", "QQQJA", "KTJJT"]);
+        assert_eq!(ranked.iter().map(Ranked::winnings).sum::<i64>(), 5905);
+    }
+
+    #[test]
+    fn test_rank_invalid_hand() {
+        let hands = vec![("KKK2X".to_string(), 1), ("2345".to_string(), 2)];
+        let err = rank_hands(&hands, Rules::Standard).unwrap_err();
+        assert_eq!(err.to_string(), "day 07: cannot solve this input: `KKK2X` is not a hand of five cards");
+        assert!(rank_hands(&hands[1..], Rules::Standard).is_err());
+        assert!(explain(&hands, Rules::Jokers).is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let hands = Day07.parse(&helpers::read_to_string("src/input/sample07.txt").unwrap()).unwrap();
+        let table = Day07.explain(&hands, 2).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[1], "    1 32T3K 32T3K   One pair        -                                   765        765");
+        assert_eq!(lines[3], "    3 T55J5 T5555   Four of a kind  Four of a kind > Two pair           684       2052");
+        assert_eq!(lines[4], "    4 QQQJA QQQQA   Four of a kind  card 1: Q > T                       483       1932");
+        assert_eq!(lines[6], "Total winnings: 5905");
+        let table = explain(&hands, Rules::Standard).unwrap();
+        assert!(table.contains("    3 KK677 -       Two pair        card 2: K > T                        28         84\n"), "{}", table);
+        assert!(table.ends_with("Total winnings: 6440\n"), "{}", table);
+    }
+
+    #[test]
+    fn test_hand_order() {
+        let hand = Hand::new("KK677", Rules::Standard).unwrap();
+        let res = hand.cmp(&Hand::new("KK677", Rules::Standard).unwrap());
         assert_eq!(res, Ordering::Equal);
-        assert_eq!(hand.sort_by_strength(("KK577", 28, Strength::TwoPair)), Ordering::Greater);
-        assert_eq!(hand.sort_by_strength(("KKA77", 28, Strength::TwoPair)), Ordering::Less);
+        assert_eq!(hand.cmp(&Hand::new("KK577", Rules::Standard).unwrap()), Ordering::Greater);
+        assert_eq!(hand.cmp(&Hand::new("KKA77", Rules::Standard).unwrap()), Ordering::Less);
+        // a stronger hand wins whatever its cards
+        assert!(Hand::new("22333", Rules::Standard).unwrap() > Hand::new("AAKKQ", Rules::Standard).unwrap());
+        // jokers are the weakest card, but count as the best card for the strength
+        assert!(Hand::new("JKKK2", Rules::Standard).unwrap() > Hand::new("TKKK2", Rules::Standard).unwrap());
+        assert!(Hand::new("JKKK2", Rules::Jokers).unwrap() < Hand::new("QQQQ2", Rules::Jokers).unwrap());
+        assert_eq!(Hand::new("JKKK2", Rules::Jokers).unwrap().strength(), Strength::Four);
+        assert!(Hand::new("KKK2", Rules::Standard).is_none());
+    }
+
+    #[test]
+    fn test_card_order() {
+        let card = |label| Card::new(label, Rules::Standard).unwrap();
+        assert!(card('A') > card('K') && card('T') > card('9') && card('3') > card('2'));
+        assert!(card('J') > card('T'));
+        assert!(Card::new('J', Rules::Jokers).unwrap() < Card::new('2', Rules::Jokers).unwrap());
+        assert_eq!(Card::new('X', Rules::Standard), None);
     }
 
     #[test]
     fn test_parse_hand() {
-        let res = parse_hand("KK677 28");
+        let res = parse_hand("KK677 28").unwrap().1;
         assert_eq!(res, ("KK677", 28));
-        assert_eq!(parse_hand("TT3KK 1000"), ("TT3KK", 1000));
+        assert_eq!(parse_hand("TT3KK 1000").unwrap().1, ("TT3KK", 1000));
+        assert!(parse_hand("TT3K 1000").is_err());
+        assert!(parse_hand("TT3KX 1000").is_err());
     }
 
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample07.txt");
+        let res = read_from_v2("src/input/sample07.txt").unwrap();
         assert_eq!(res, 5905);
     }
 
     #[test]
     fn test_read_from_v2_b() {
-        let res = read_from_v2("src/input/sample07b.txt");
+        let res = read_from_v2("src/input/sample07b.txt").unwrap();
         assert_eq!(res, 1369);
     }
 
     #[test]
     fn test_read_from_v2_c() {
-        let res = read_from_v2("src/input/sample07c.txt");
+        let res = read_from_v2("src/input/sample07c.txt").unwrap();
         assert_eq!(res, 1387);
     }
 
     #[test]
     fn test_read_from_v2_d() {
-        let res = read_from_v2("src/input/sample07d.txt");
+        let res = read_from_v2("src/input/sample07d.txt").unwrap();
         assert_eq!(res, 3667);
     }
 
     #[test]
     fn test_read_from_v2_e() {
-        let res = read_from_v2("src/input/sample07e.txt");
+        let res = read_from_v2("src/input/sample07e.txt").unwrap();
         // assert_eq!(res, 12); // added one line > result change
         assert_eq!(res, 19);
     }
@@ -333,7 +525,7 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day07.txt").unwrap();
         assert_eq!(res, 249620106);
     }
 }
\ No newline at end of file
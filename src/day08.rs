@@ -1,124 +1,455 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-use nom::Parser;
+use nom::{IResult, Parser};
 use nom::bytes::complete::tag;
 use nom::character::complete::alphanumeric1;
-use nom::error::Error;
 use nom::sequence::{delimited, pair, preceded, terminated};
-use num::integer::lcm;
+use num::Integer;
 
+use crate::cycle;
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day08.txt")
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Parsed = Documents;
+
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn title(&self) -> &'static str {
+        "Haunted Wasteland"
+    }
+
+    fn parse(&self, input: &str) -> Result<Documents, AocError> {
+        let sample = helpers::lines(input);
+        if sample.first().is_none_or(|line| line.is_empty()) {
+            return Err(AocError::parse(8, "", 1, "", "expected a line of L/R instructions"));
+        }
+        let instructions = parse_instructions(sample[0].as_str());
+        let network = Network::from_rows(&parse_maps(&sample)?);
+        Ok(Documents { instructions, network })
+    }
+
+    fn part_1(&self, documents: &Documents) -> Result<i64, AocError> {
+        let walks = documents.walks(&["AAA"], |node| node == "ZZZ")?;
+        first_common_hit(&walks)?.ok_or_else(|| AocError::unsolvable(8, "ZZZ cannot be reached from AAA"))
+    }
+
+    fn part_2(&self, documents: &Documents) -> Result<i64, AocError> {
+        let network = &documents.network;
+        let starts: Vec<&str> = network.defined().map(|node| network.name(node)).filter(|name| name.ends_with('A')).collect();
+        if starts.is_empty() {
+            return Err(AocError::unsolvable(8, "no node ends with A"));
+        }
+        let walks = documents.walks(&starts, |node| node.ends_with('Z'))?;
+        first_common_hit(&walks)?.ok_or_else(|| AocError::unsolvable(8, "the walks never are on nodes ending with Z at the same time"))
+    }
 }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day08.txt")
+
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day08.part_1(&Day08.parse(input)?)
 }
 
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
+}
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let instructions = parse_instructions(sample.first().unwrap().as_str());
-    let maps: HashMap<&str, (&str, &str)> = sample.iter().skip(2).map(|line| parse_map(line.as_str())).collect();
-    process_instructions(instructions, "AAA", "ZZZ", maps)
+pub struct Documents {
+    instructions: Vec<char>,
+    network: Network,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct DesertMap {
-    from: &'static str,
-    to_left: &'static str,
-    to_right: &'static str,
+impl Documents {
+    // Every problem that would make a walk from one of the `starts` fail or never reach a target
+    pub fn validate<F: Fn(&str) -> bool>(&self, starts: &[&str], is_target: F) -> Vec<Diagnostic> {
+        let mut diagnostics = self.structure();
+        if diagnostics.is_empty() {
+            diagnostics.extend(starts.iter().filter_map(|start| self.walk_from(start, &is_target).err()));
+        }
+        diagnostics
+    }
+
+    // The walk from each of the `starts`, the first diagnostic as an error if one of them cannot be followed
+    // or never reaches a target
+    pub fn walks<F: Fn(&str) -> bool>(&self, starts: &[&str], is_target: F) -> Result<Vec<Walk>, AocError> {
+        if let Some(diagnostic) = self.structure().into_iter().next() {
+            return Err(self.to_error(diagnostic));
+        }
+        starts.iter()
+            .map(|start| self.walk_from(start, &is_target).map_err(|diagnostic| self.to_error(diagnostic)))
+            .collect()
+    }
+
+    // instructions other than L or R, and nodes without a definition
+    fn structure(&self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = self.instructions.iter()
+            .enumerate()
+            .filter(|(_, &step)| step != 'L' && step != 'R')
+            .map(|(idx, &found)| Diagnostic::InvalidInstruction { column: idx + 1, found })
+            .collect();
+        let network = &self.network;
+        diagnostics.extend((0..network.len())
+            .filter(|&node| network.edges(node).is_none())
+            .map(|node| {
+                let referenced_by = network.defined()
+                    .find(|&from| network.edges(from).is_some_and(|(left, right)| left == node || right == node))
+                    .map_or("", |from| network.name(from));
+                Diagnostic::UndefinedNode { node: network.name(node).to_string(), referenced_by: referenced_by.to_string() }
+            }));
+        diagnostics
+    }
+
+    // only called once `structure` found nothing, every node is defined
+    fn walk_from<F: Fn(&str) -> bool>(&self, start: &str, is_target: F) -> Result<Walk, Diagnostic> {
+        let network = &self.network;
+        let node = network.id(start)
+            .filter(|&node| network.edges(node).is_some())
+            .ok_or_else(|| Diagnostic::MissingStart { node: start.to_string() })?;
+        let walk = network.walk(&self.instructions, node, |node| is_target(network.name(node)))
+            .expect("every node of a validated network is defined");
+        if walk.hits.is_empty() {
+            return Err(Diagnostic::Unreachable { start: start.to_string(), explored: walk.offset + walk.period });
+        }
+        Ok(walk)
+    }
+
+    fn to_error(&self, diagnostic: Diagnostic) -> AocError {
+        match diagnostic {
+            Diagnostic::InvalidInstruction { column, found } => {
+                let line: String = self.instructions.iter().collect();
+                let remaining: String = self.instructions[column - 1..].iter().collect();
+                AocError::parse(8, &line, 1, &remaining, format!("expected `L` or `R`, found `{}`", found))
+            }
+            diagnostic => AocError::unsolvable(8, diagnostic.to_string()),
+        }
+    }
 }
 
-fn parse_instructions(input: &str) -> Vec<char> {
-    input.chars().collect()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    // `column` is 1-based in the line of instructions
+    InvalidInstruction { column: usize, found: char },
+    UndefinedNode { node: String, referenced_by: String },
+    MissingStart { node: String },
+    // every (node, instruction index) state reachable from `start` was explored without finding a target
+    Unreachable { start: String, explored: usize },
 }
 
-fn parse_map(input: &str) -> (&str, (&str, &str)) {
-    let (input, node): (&str, &str) = terminated(alphanumeric1::<&str, Error<&str>>, tag(" = ")).parse(input).unwrap();
-    let (_, left_or_right): (&str, (&str, &str)) = delimited(tag("("), pair(alphanumeric1::<&str, Error<&str>>, preceded(tag(", "), alphanumeric1)), tag(")")).parse(input).unwrap();
-    (node, left_or_right)
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::InvalidInstruction { column, found } => write!(f, "instruction {} is `{}`, expected `L` or `R`", column, found),
+            Diagnostic::UndefinedNode { node, referenced_by } => write!(f, "node {} is not defined (referenced by {})", node, referenced_by),
+            Diagnostic::MissingStart { node } => write!(f, "starting node {} is not defined", node),
+            Diagnostic::Unreachable { start, explored } => write!(f, "no target can be reached from {} ({} states explored)", start, explored),
+        }
+    }
 }
 
-fn process_instructions(instructions: Vec<char>, starting_node: &str, last_node: &str, maps: HashMap<&str, (&str, &str)>) -> i64 {
-    let mut steps: usize = 0;
-    let mut current_node = starting_node;
-    let instructions_len = instructions.len();
-    while current_node != last_node {
-        let step = instructions.get(steps % instructions_len).unwrap();
-        current_node = if *step == 'L' {
-            maps.get(current_node).unwrap().0
+// `NODE = (LEFT, RIGHT)`
+pub type Row<'a> = (&'a str, (&'a str, &'a str));
+
+// The nodes of the desert with interned ids, in the order they first appear in the input
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Network {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    // (left, right) of each node, `None` for a node that is referenced but never defined
+    edges: Vec<Option<(usize, usize)>>,
+}
+
+impl Network {
+    pub fn from_rows(rows: &[Row]) -> Network {
+        let mut network = Network::default();
+        // the defined nodes get the first ids
+        for (node, _) in rows {
+            network.intern(node);
+        }
+        for (node, (left, right)) in rows {
+            let edge = (network.intern(left), network.intern(right));
+            let id = network.ids[*node];
+            network.edges[id] = Some(edge);
+        }
+        network
+    }
+
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        self.names.push(name.to_string());
+        self.edges.push(None);
+        self.ids.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, node: usize) -> &str {
+        &self.names[node]
+    }
+
+    pub fn edges(&self, node: usize) -> Option<(usize, usize)> {
+        self.edges[node]
+    }
+
+    // the nodes with a `NODE = (LEFT, RIGHT)` line
+    pub fn defined(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(|&node| self.edges[node].is_some())
+    }
+
+    pub fn next(&self, node: usize, step: char) -> Result<usize, AocError> {
+        let (left, right) = self.edges[node]
+            .ok_or_else(|| AocError::unsolvable(8, format!("node {} is not defined", self.name(node))))?;
+        Ok(if step == 'L' { left } else { right })
+    }
+
+    // Follow the instructions from `start` until a (node, instruction index) state repeats
+    pub fn walk<F: Fn(usize) -> bool>(&self, instructions: &[char], start: usize, is_target: F) -> Result<Walk, AocError> {
+        let mut error = None;
+        let found = cycle::find_cycle((start, 0), |&(node, idx): &(usize, usize)| {
+            match self.next(node, instructions[idx]) {
+                Ok(next) => (next, (idx + 1) % instructions.len()),
+                Err(e) => {
+                    // stay on the same state, it ends the walk right away
+                    error.get_or_insert(e);
+                    (node, idx)
+                }
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        let hits = found.states().iter().enumerate()
+            .filter(|(_, &(node, _))| is_target(node))
+            .map(|(steps, _)| steps)
+            .collect();
+        Ok(Walk { offset: found.start, period: found.length, hits })
+    }
+}
+
+// A walk enters a loop of `period` steps after `offset` steps, it is on a target node after each of the
+// `hits` steps, and after `hit + n * period` steps for the hits in the loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walk {
+    pub offset: usize,
+    pub period: usize,
+    pub hits: Vec<usize>,
+}
+
+impl Walk {
+    pub fn is_hit(&self, steps: usize) -> bool {
+        let steps = if steps < self.offset { steps } else { self.offset + (steps - self.offset) % self.period };
+        self.hits.binary_search(&steps).is_ok()
+    }
+
+    fn periodic_hits(&self) -> impl Iterator<Item = usize> + '_ {
+        self.hits.iter().copied().filter(|&hit| hit >= self.offset)
+    }
+}
+
+// Smallest number of steps after which every walk is on a target node, `None` if they never are at the same time.
+// Once all walks are in their loop each hit is a congruence `steps = hit (mod period)`, solved with the CRT:
+// folding `lcm` over the first hits only works when every walk hits exactly at its period.
+pub fn first_common_hit(walks: &[Walk]) -> Result<Option<i64>, AocError> {
+    let prefix = walks.iter().map(|walk| walk.offset).max().unwrap_or(0);
+    if let Some(steps) = (0..prefix).find(|&steps| walks.iter().all(|walk| walk.is_hit(steps))) {
+        return Ok(Some(steps as i64));
+    }
+
+    let too_many_steps = || AocError::unsolvable(8, "the walks only meet after more steps than an i64 can count");
+    let mut congruences: Vec<(i128, i128)> = vec![(0, 1)];
+    for walk in walks {
+        let period = walk.period as i128;
+        let mut combined = vec![];
+        for &congruence in &congruences {
+            for hit in walk.periodic_hits() {
+                match crt(congruence, (hit as i128 % period, period)) {
+                    Ok(Some(congruence)) => combined.push(congruence),
+                    Ok(None) => {}
+                    Err(()) => return Err(too_many_steps()),
+                }
+            }
+        }
+        congruences = combined;
+        congruences.sort();
+        congruences.dedup();
+    }
+    let prefix = prefix as i128;
+    // the first step of each congruence once every walk is in its loop
+    let first_steps: Option<Vec<i128>> = congruences.into_iter()
+        .map(|(residue, modulus)| if residue >= prefix {
+            Some(residue)
         } else {
-            maps.get(current_node).unwrap().1
-        };
-        steps += 1;
+            ((prefix - residue - 1) / modulus + 1).checked_mul(modulus)?.checked_add(residue)
+        })
+        .collect();
+    match first_steps.ok_or_else(too_many_steps)?.into_iter().min() {
+        Some(steps) => i64::try_from(steps).map(Some).map_err(|_| too_many_steps()),
+        None => Ok(None),
     }
-    steps as i64
 }
 
+// Combine `x = r1 (mod m1)` and `x = r2 (mod m2)` into `x = r (mod lcm(m1, m2))`, `Ok(None)` if there is no solution
+// and `Err` if the combined congruence does not fit in an i128
+fn crt((r1, m1): (i128, i128), (r2, m2): (i128, i128)) -> Result<Option<(i128, i128)>, ()> {
+    let gcd = m1.extended_gcd(&m2);
+    if (r2 - r1) % gcd.gcd != 0 {
+        return Ok(None);
+    }
+    let combine = || {
+        let modulus = (m1 / gcd.gcd).checked_mul(m2)?;
+        let k = ((r2 - r1) / gcd.gcd).checked_mul(gcd.x)?.rem_euclid(m2 / gcd.gcd);
+        Some((m1.checked_mul(k)?.checked_add(r1)?.rem_euclid(modulus), modulus))
+    };
+    combine().map(Some).ok_or(())
+}
+
+// The network as a Graphviz graph, `dot -Tsvg` renders it. Nodes ending with A are green, with Z red, undefined ones dashed.
+// With `from`, the walk from this node is drawn in blue until it reaches a node ending with Z or loops.
+pub fn to_dot(documents: &Documents, from: Option<&str>) -> Result<String, AocError> {
+    let network = &documents.network;
+    let path: HashSet<(usize, char)> = match from {
+        None => HashSet::new(),
+        Some(name) => {
+            let start = network.id(name).ok_or_else(|| AocError::unsolvable(8, format!("node {} is not defined", name)))?;
+            walked_edges(documents, start)
+        }
+    };
+    let on_path = |node: usize| path.iter().any(|&(from, _)| from == node);
 
-fn process_instructions_v2(instructions: &Vec<char>, starting_node: &str, maps: &HashMap<&str, (&str, &str)>) -> i64 {
-    let mut steps: usize = 0;
-    let mut current_node = starting_node;
-    let instructions_len = instructions.len();
-    while current_node.chars().nth(2).unwrap() != 'Z' {
-        let step = instructions.get(steps % instructions_len).unwrap();
-        current_node = if *step == 'L' {
-            maps.get(current_node).unwrap().0
+    let mut dot = String::from("digraph day08 {\n");
+    for node in 0..network.len() {
+        let name = network.name(node);
+        let mut attributes = vec![];
+        if name.ends_with('A') {
+            attributes.push("style=filled, fillcolor=palegreen");
+        } else if name.ends_with('Z') {
+            attributes.push("style=filled, fillcolor=lightcoral");
+        } else if network.edges(node).is_none() {
+            attributes.push("style=dashed");
+        }
+        if on_path(node) {
+            attributes.push("color=blue, penwidth=2");
+        }
+        if attributes.is_empty() {
+            dot.push_str(&format!("    \"{}\";\n", name));
         } else {
-            maps.get(current_node).unwrap().1
-        };
-        steps += 1;
+            dot.push_str(&format!("    \"{}\" [{}];\n", name, attributes.join(", ")));
+        }
+    }
+    for node in network.defined() {
+        let (left, right) = network.edges(node).unwrap();
+        let edges = if left == right { vec![(left, "L/R")] } else { vec![(left, "L"), (right, "R")] };
+        for (to, label) in edges {
+            let walked = label.chars().any(|step| path.contains(&(node, step)));
+            let colour = if walked { ", color=blue, penwidth=2" } else { "" };
+            dot.push_str(&format!("    \"{}\" -> \"{}\" [label=\"{}\"{}];\n", network.name(node), network.name(to), label, colour));
+        }
+    }
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+// The (node, instruction) edges followed from `start` until a node ending with Z, an undefined node or a repeated state
+fn walked_edges(documents: &Documents, start: usize) -> HashSet<(usize, char)> {
+    let network = &documents.network;
+    let mut edges = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut state = (start, 0);
+    while !network.name(state.0).ends_with('Z') && seen.insert(state) {
+        let (node, idx) = state;
+        let step = documents.instructions[idx];
+        match network.next(node, step) {
+            Ok(next) if step == 'L' || step == 'R' => {
+                edges.insert((node, step));
+                state = (next, (idx + 1) % documents.instructions.len());
+            }
+            _ => break,
+        }
     }
-    steps as i64
+    edges
 }
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
+pub fn dot(input: &str, from: Option<&str>) -> Result<String, AocError> {
+    to_dot(&Day08.parse(input)?, from)
+}
 
-    let instructions = &parse_instructions(sample.first().unwrap().as_str());
-    let rows: Vec<(&str, (&str, &str))> = sample.iter().skip(2).map(|line| parse_map(line.as_str())).collect();
-    let maps: &HashMap<&str, (&str, &str)> = &rows.clone().into_iter().collect();
-    let starting_nodes: Vec<&str> = rows.into_iter().filter_map(|(node, _)| if node.ends_with('A') { Some(node) } else { None }).collect();
+fn parse_instructions(input: &str) -> Vec<char> {
+    input.chars().collect()
+}
 
-    let res: i64 = starting_nodes.into_iter().map(|node| {
-        process_instructions_v2(instructions, node, maps)
-    }).fold(1, lcm);
-    res
+fn parse_maps(sample: &[String]) -> Result<Vec<Row<'_>>, AocError> {
+    sample.iter()
+        .enumerate()
+        .skip(2)
+        .map(|(idx, line)| helpers::parse_all(8, idx, line, parse_map))
+        .collect()
+}
+
+fn parse_map(input: &str) -> IResult<&str, Row<'_>> {
+    let (input, node): (&str, &str) = terminated(alphanumeric1, tag(" = ")).parse(input)?;
+    let (input, left_or_right): (&str, (&str, &str)) = delimited(tag("("), pair(alphanumeric1, preceded(tag(", "), alphanumeric1)), tag(")")).parse(input)?;
+    Ok((input, (node, left_or_right)))
+}
+
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day08.part_2(&Day08.parse(input)?)
+}
+
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use super::*;
 
+    // steps from `start` to the first node matching `is_target`
+    fn steps<F: Fn(&str) -> bool>(instructions: &[char], rows: &[Row], start: &str, is_target: F) -> Result<i64, AocError> {
+        let network = Network::from_rows(rows);
+        let walk = network.walk(instructions, network.id(start).unwrap(), |node| is_target(network.name(node)))?;
+        Ok(first_common_hit(&[walk])?.unwrap())
+    }
+
     #[test]
     fn test_part_1() {
-        let res = part_1();
+        let res = read_from("src/input/day08.txt").unwrap();
         assert_eq!(res, 13207);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample08.txt");
+        let res = read_from("src/input/sample08.txt").unwrap();
         assert_eq!(res, 2);
     }
 
     #[test]
     fn test_read_from_b() {
-        let res = read_from("src/input/sample08b.txt");
+        let res = read_from("src/input/sample08b.txt").unwrap();
         assert_eq!(res, 6);
     }
 
     #[test]
     fn test_parse_map() {
-        let res = parse_map("AAA = (BBB, CCC)");
+        let res = parse_map("AAA = (BBB, CCC)").unwrap().1;
         assert_eq!(res.0, "AAA");
         assert_eq!(res.1.0, "BBB");
         assert_eq!(res.1.1, "CCC");
@@ -127,7 +458,7 @@ mod tests {
     #[test]
     fn test_parse_instructions() {
         let res = parse_instructions("RLLRR");
-        assert_eq!(*res.get(0).unwrap(), 'R');
+        assert_eq!(res[0], 'R');
         assert_eq!(*res.get(1).unwrap(), 'L');
         assert_eq!(*res.get(2).unwrap(), 'L');
         assert_eq!(*res.get(3).unwrap(), 'R');
@@ -136,42 +467,164 @@ mod tests {
 
     #[test]
     fn test_process_instruction() {
-        let res = process_instructions(vec!['R'], "AAA", "AAA", HashMap::from([("AAA", ("AAA", "AAA"))]));
+        let res = steps(&['R'], &[("AAA", ("AAA", "AAA"))], "AAA", |node| node == "AAA").unwrap();
         assert_eq!(res, 0);
-        let res = process_instructions(vec!['R'], "BBB", "AAA", HashMap::from([("BBB", ("BBB", "AAA")), ("AAA", ("AAA", "AAA"))]));
+        let res = steps(&['R'], &[("BBB", ("BBB", "AAA")), ("AAA", ("AAA", "AAA"))], "BBB", |node| node == "AAA").unwrap();
         assert_eq!(res, 1);
-        let res = process_instructions(vec!['L', 'R'], "BBB", "AAA", HashMap::from([("BBB", ("BBB", "AAA")), ("AAA", ("AAA", "AAA"))]));
+        let res = steps(&['L', 'R'], &[("BBB", ("BBB", "AAA")), ("AAA", ("AAA", "AAA"))], "BBB", |node| node == "AAA").unwrap();
         assert_eq!(res, 2);
-        let res = process_instructions(vec!['L', 'R'], "AAA", "AAA", HashMap::from([("BBB", ("BBB", "AAA")), ("AAA", ("AAA", "AAA"))]));
+        let res = steps(&['L', 'R'], &[("BBB", ("BBB", "AAA")), ("AAA", ("AAA", "AAA"))], "AAA", |node| node == "AAA").unwrap();
         assert_eq!(res, 0);
     }
 
     #[test]
     fn test_process_instruction_v2() {
-        let res = process_instructions_v2(&vec!['L', 'R'], "11A", &HashMap::from([
+        let res = steps(&['L', 'R'], &[
             ("11A", ("11B", "XXX")),
             ("11B", ("XXX", "11Z")),
             ("11Z", ("11B", "XXX"))
-        ]));
+        ], "11A", |node| node.ends_with('Z')).unwrap();
         assert_eq!(res, 2);
-        let res = process_instructions_v2(&vec!['L', 'R'], "22A", &HashMap::from([
+        let res = steps(&['L', 'R'], &[
             ("22A", ("22B", "XXX")),
             ("22B", ("22C", "22C")),
             ("22C", ("22Z", "22Z")),
             ("22Z", ("22B", "22B"))
-        ]));
+        ], "22A", |node| node.ends_with('Z')).unwrap();
         assert_eq!(res, 3);
     }
 
+    #[test]
+    fn test_undefined_node() {
+        let err = steps(&['L'], &[("AAA", ("BBB", "AAA"))], "AAA", |node| node == "ZZZ").unwrap_err();
+        assert_eq!(err.to_string(), "day 08: cannot solve this input: node BBB is not defined");
+    }
+
+    #[test]
+    fn test_network() {
+        let network = Network::from_rows(&[("AAA", ("BBB", "CCC")), ("BBB", ("AAA", "AAA"))]);
+        assert_eq!(network.len(), 3);
+        assert_eq!(network.id("CCC"), Some(2));
+        assert_eq!(network.edges(0), Some((1, 2)));
+        assert_eq!(network.edges(2), None);
+        assert_eq!(network.defined().collect::<Vec<usize>>(), vec![0, 1]);
+        assert_eq!(network.next(0, 'R').unwrap(), 2);
+        assert!(network.next(2, 'L').is_err());
+    }
+
+    #[test]
+    fn test_walk() {
+        let network = Network::from_rows(&[("11A", ("11B", "XXX")), ("11B", ("XXX", "11Z")), ("11Z", ("11B", "XXX"))]);
+        let walk = network.walk(&['L', 'R'], 0, |node| network.name(node).ends_with('Z')).unwrap();
+        assert_eq!(walk, Walk { offset: 1, period: 2, hits: vec![2] });
+        assert!(walk.is_hit(4) && walk.is_hit(1000));
+        assert!(!walk.is_hit(0) && !walk.is_hit(3));
+    }
+
+    #[test]
+    fn test_first_common_hit() {
+        // hits after 1, 5, 9... and 3, 9, 15... steps: folding lcm over the first hits would give 3
+        let a = Walk { offset: 0, period: 4, hits: vec![1] };
+        let b = Walk { offset: 0, period: 6, hits: vec![3] };
+        assert_eq!(first_common_hit(&[a.clone(), b.clone()]).unwrap(), Some(9));
+        // a hit before entering the loop
+        let c = Walk { offset: 2, period: 2, hits: vec![1, 3] };
+        assert_eq!(first_common_hit(&[a.clone(), c.clone()]).unwrap(), Some(1));
+        // several hits in the loop
+        let d = Walk { offset: 0, period: 6, hits: vec![2, 4] };
+        assert_eq!(first_common_hit(&[b.clone(), d.clone()]).unwrap(), None);
+        assert_eq!(first_common_hit(&[Walk { offset: 0, period: 5, hits: vec![3] }, d]).unwrap(), Some(8));
+        // the classic input: every walk hits at its period
+        let walks: Vec<Walk> = [3, 5, 7].iter().map(|&period| Walk { offset: 1, period, hits: vec![period] }).collect();
+        assert_eq!(first_common_hit(&walks).unwrap(), Some(105));
+        // large coprime periods: the combined period needs more than 64 bits but the walks meet early
+        let walks = [(1 << 61) - 1, (1 << 31) - 1].map(|period| Walk { offset: 0, period, hits: vec![5] });
+        assert_eq!(first_common_hit(&walks).unwrap(), Some(5));
+        let walks = [(1 << 61) - 1, (1 << 31) - 1].map(|period| Walk { offset: 0, period, hits: vec![period - 5] });
+        assert!(first_common_hit(&walks).is_err());
+        // two primes close to 2^64, their product does not fit in an i128
+        let walks = [18446744073709551557, 18446744073709551533].map(|period| Walk { offset: 0, period, hits: vec![1] });
+        assert!(first_common_hit(&walks).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let documents = Day08.parse("LR\n\nAAA = (ZZZ, BBB)\nBBB = (BBB, BBB)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert_eq!(documents.validate(&["AAA"], |node| node == "ZZZ"), vec![]);
+        assert_eq!(documents.validate(&["AAA", "CCC"], |node| node == "ZZZ"), vec![
+            Diagnostic::MissingStart { node: "CCC".to_string() },
+        ]);
+        // BBB only leads to itself
+        assert_eq!(documents.validate(&["AAA", "BBB"], |node| node == "ZZZ"), vec![
+            Diagnostic::Unreachable { start: "BBB".to_string(), explored: 2 },
+        ]);
+
+        let documents = Day08.parse("LXR\n\nAAA = (BBB, CCC)\nBBB = (AAA, AAA)").unwrap();
+        assert_eq!(documents.validate(&["AAA"], |node| node == "ZZZ"), vec![
+            Diagnostic::InvalidInstruction { column: 2, found: 'X' },
+            Diagnostic::UndefinedNode { node: "CCC".to_string(), referenced_by: "AAA".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        let err = solve("LXR\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(err.to_string(), "day 08: parse error at line 1, column 2: expected `L` or `R`, found `X`\n    LXR\n     ^");
+        let err = solve("L\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(err.to_string(), "day 08: cannot solve this input: node BBB is not defined (referenced by AAA)");
+        let err = solve("L\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(err.to_string(), "day 08: cannot solve this input: no target can be reached from AAA (1 states explored)");
+        let err = solve_v2("L\n\nBBB = (BBB, BBB)").unwrap_err();
+        assert_eq!(err.to_string(), "day 08: cannot solve this input: no node ends with A");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let input = "LR\n\nAAA = (BBB, CCC)\nBBB = (DDD, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(dot(input, None).unwrap(), [
+            "digraph day08 {",
+            "    \"AAA\" [style=filled, fillcolor=palegreen];",
+            "    \"BBB\";",
+            "    \"ZZZ\" [style=filled, fillcolor=lightcoral];",
+            "    \"CCC\" [style=dashed];",
+            "    \"DDD\" [style=dashed];",
+            "    \"AAA\" -> \"BBB\" [label=\"L\"];",
+            "    \"AAA\" -> \"CCC\" [label=\"R\"];",
+            "    \"BBB\" -> \"DDD\" [label=\"L\"];",
+            "    \"BBB\" -> \"ZZZ\" [label=\"R\"];",
+            "    \"ZZZ\" -> \"ZZZ\" [label=\"L/R\"];",
+            "}\n",
+        ].join("\n"));
+
+        let with_path = dot(input, Some("AAA")).unwrap();
+        let walked: Vec<&str> = with_path.lines().filter(|line| line.contains("color=blue")).collect();
+        assert_eq!(walked, vec![
+            "    \"AAA\" [style=filled, fillcolor=palegreen, color=blue, penwidth=2];",
+            "    \"BBB\" [color=blue, penwidth=2];",
+            "    \"AAA\" -> \"BBB\" [label=\"L\", color=blue, penwidth=2];",
+            "    \"BBB\" -> \"ZZZ\" [label=\"R\", color=blue, penwidth=2];",
+        ]);
+        assert!(dot(input, Some("XXX")).is_err());
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((1, 4), (3, 6)), Ok(Some((9, 12))));
+        assert_eq!(crt((0, 1), (2, 5)), Ok(Some((2, 5))));
+        assert_eq!(crt((1, 4), (2, 6)), Ok(None));
+        // two primes close to 2^64, their product does not fit in an i128
+        assert_eq!(crt((1, 18446744073709551557), (2, 18446744073709551533)), Err(()));
+    }
+
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample08c.txt");
+        let res = read_from_v2("src/input/sample08c.txt").unwrap();
         assert_eq!(res, 6);
     }
 
     #[test]
     fn test_part_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day08.txt").unwrap();
         assert_eq!(res, 12324145107121);
     }
 }
\ No newline at end of file
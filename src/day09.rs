@@ -1,43 +1,59 @@
-use itertools::Itertools;
 use nom::{IResult, Parser};
 use nom::bytes::complete::tag;
 use nom::character::complete;
 use nom::multi::separated_list0;
+use num::{CheckedAdd, CheckedMul, Zero};
+use num::rational::Ratio;
 
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day09.txt")
-}
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Parsed = Vec<Vec<i64>>;
+
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn title(&self) -> &'static str {
+        "Mirage Maintenance"
+    }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day09.txt")
+    fn parse(&self, input: &str) -> Result<Vec<Vec<i64>>, AocError> {
+        input.lines()
+            .enumerate()
+            .map(|(idx, line)| helpers::parse_all(9, idx, line, parse_number))
+            .collect()
+    }
+
+    fn part_1(&self, sequences: &Vec<Vec<i64>>) -> Result<i64, AocError> {
+        sum_extrapolated(sequences, |sequence| sequence.len() as i64)
+    }
+
+    fn part_2(&self, sequences: &Vec<Vec<i64>>) -> Result<i64, AocError> {
+        sum_extrapolated(sequences, |_| -1)
+    }
 }
 
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day09.part_1(&Day09.parse(input)?)
+}
 
-    let value: i64 = sample.iter()
-        .map(|line| parse_number(line).unwrap().1)
-        .map(|sequence| process(sequence))
-        .map(|diffs| generate_last(diffs))
-        .sum();
-    value
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
 }
 
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day09.part_2(&Day09.parse(input)?)
+}
 
-    let value: i64 = sample.iter()
-        .map(|line| parse_number(line).unwrap().1)
-        .map(|sequence| process(sequence))
-        .map(|diffs| generate_first(diffs))
-        .sum();
-    value
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 fn parse_number(input: &str) -> IResult<&str, Vec<i64>> {
@@ -45,48 +61,107 @@ fn parse_number(input: &str) -> IResult<&str, Vec<i64>> {
     Ok(res)
 }
 
-fn process(sequence: Vec<i64>) -> Vec<Vec<i64>> {
-    let mut lists = vec![sequence.clone()];
-    let mut res: Vec<i64> = sequence.iter().tuple_windows().map(|(first, second)| {
-        second - first
-    }).collect();
-    lists.push(res.clone());
-
-    while !res.iter().all(|&e| e == 0) {
-        res = res.iter().tuple_windows().map(|(first, second)| {
-            second - first
-        }).collect();
-        lists.push(res.clone());
-    }
+// Sum of the value of each sequence at the index given by `index`
+fn sum_extrapolated<F: Fn(&[i64]) -> i64>(sequences: &[Vec<i64>], index: F) -> Result<i64, AocError> {
+    sequences.iter().try_fold(0_i64, |sum, sequence| {
+        let value = Polynomial::fit(sequence)?.at(index(sequence))?;
+        sum.checked_add(value).ok_or_else(|| AocError::unsolvable(9, "the sum of the extrapolated values overflows"))
+    })
+}
 
-    lists
+/// The polynomial of lowest degree going through every value of a sequence, the first value being at index 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    // Newton forward differences at index 0, the last one is not zero
+    differences: Vec<i128>,
 }
 
+impl Polynomial {
+    pub fn fit(sequence: &[i64]) -> Result<Polynomial, AocError> {
+        let mut row: Vec<i128> = sequence.iter().map(|&value| value as i128).collect();
+        let mut differences = vec![];
+        while let Some(&first) = row.first() {
+            differences.push(first);
+            row = row.windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<_>>()
+                .ok_or_else(|| AocError::unsolvable(9, "the differences of the sequence overflow"))?;
+        }
+        while differences.last() == Some(&0) {
+            differences.pop();
+        }
+        Ok(Polynomial { differences })
+    }
 
-fn generate_last(diffs: Vec<Vec<i64>>) -> i64 {
-    let res = diffs.iter().rev().fold(0, |add_to_last, list| list.last().unwrap() + add_to_last);
-    res
-}
+    // 0 for a constant sequence, including an all zero one
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
 
-fn generate_first(diffs: Vec<Vec<i64>>) -> i64 {
-    let res = diffs.iter().rev().fold(0, |add_to_first, list| list.first().unwrap() - add_to_first);
-    res
+    // The value at any index, before the sequence for a negative one.
+    // p(x) = sum of the k-th difference times C(x, k), where C(x, k) = x (x - 1) ... (x - k + 1) / k!
+    pub fn at(&self, index: i64) -> Result<i64, AocError> {
+        let overflow = || AocError::unsolvable(9, format!("the value at index {} overflows", index));
+        let mut value: Ratio<i128> = Ratio::zero();
+        let mut binomial: Ratio<i128> = Ratio::from_integer(1);
+        for (k, &difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                let factor = Ratio::new(index as i128 - (k as i128 - 1), k as i128);
+                binomial = binomial.checked_mul(&factor).ok_or_else(overflow)?;
+            }
+            let term = binomial.checked_mul(&Ratio::from_integer(difference)).ok_or_else(overflow)?;
+            value = value.checked_add(&term).ok_or_else(overflow)?;
+        }
+        // C(x, k) is an integer for any integer x, so is the value
+        i64::try_from(value.to_integer()).map_err(|_| overflow())
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+    use quickcheck::QuickCheck;
+
     use super::*;
 
+    // the difference triangle, kept to check `Polynomial` against it
+    fn process(sequence: Vec<i64>) -> Vec<Vec<i64>> {
+        let mut lists = vec![sequence.clone()];
+        let mut res: Vec<i64> = sequence.iter().tuple_windows().map(|(first, second)| {
+            second - first
+        }).collect();
+        lists.push(res.clone());
+
+        while !res.iter().all(|&e| e == 0) {
+            res = res.iter().tuple_windows().map(|(first, second)| {
+                second - first
+            }).collect();
+            lists.push(res.clone());
+        }
+
+        lists
+    }
+
+    fn generate_last(diffs: Vec<Vec<i64>>) -> i64 {
+        let res = diffs.iter().rev().fold(0, |add_to_last, list| list.last().unwrap_or(&0) + add_to_last);
+        res
+    }
+
+    fn generate_first(diffs: Vec<Vec<i64>>) -> i64 {
+        let res = diffs.iter().rev().fold(0, |add_to_first, list| list.first().unwrap_or(&0) - add_to_first);
+        res
+    }
+
     #[test]
     fn test_part_1() {
-        let res = part_1();
+        let res = read_from("src/input/day09.txt").unwrap();
         assert_eq!(res, 1782868781);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample09.txt");
+        let res = read_from("src/input/sample09.txt").unwrap();
         assert_eq!(res, 114);
     }
 
@@ -127,15 +202,56 @@ mod tests {
         assert_eq!(res, 5);
     }
 
+    #[test]
+    fn test_polynomial() {
+        let polynomial = Polynomial::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(polynomial.degree(), 3);
+        assert_eq!(polynomial.at(6).unwrap(), 68);
+        assert_eq!(polynomial.at(-1).unwrap(), 5);
+        assert_eq!(polynomial.at(0).unwrap(), 10);
+        // n^2 + n + 1 after the first value
+        let polynomial = Polynomial::fit(&[1, 3, 7, 13]).unwrap();
+        assert_eq!(polynomial.degree(), 2);
+        assert_eq!(polynomial.at(-1000).unwrap(), 999_001);
+        assert_eq!(polynomial.at(1_000_000).unwrap(), 1_000_001_000_001);
+        assert_eq!(Polynomial::fit(&[0, 0, 0]).unwrap().degree(), 0);
+        assert_eq!(Polynomial::fit(&[]).unwrap().at(5).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_polynomial_overflow() {
+        let polynomial = Polynomial::fit(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(
+            polynomial.at(i64::MAX).unwrap_err().to_string(),
+            format!("day 09: cannot solve this input: the value at index {} overflows", i64::MAX)
+        );
+        assert!(Polynomial::fit(&[i64::MAX, i64::MIN]).unwrap().at(2).is_err());
+        assert_eq!(Polynomial::fit(&[i64::MAX, i64::MIN]).unwrap().at(1).unwrap(), i64::MIN);
+        assert!(solve("9223372036854775807\n1").is_err());
+    }
+
+    #[test]
+    fn test_polynomial_matches_triangle() {
+        fn prop(sequence: Vec<i16>) -> bool {
+            let sequence: Vec<i64> = sequence.into_iter().take(8).map(i64::from).collect();
+            let polynomial = Polynomial::fit(&sequence).unwrap();
+            let triangle = process(sequence.clone());
+            sequence.iter().enumerate().all(|(idx, &value)| polynomial.at(idx as i64).unwrap() == value)
+                && polynomial.at(sequence.len() as i64).unwrap() == generate_last(triangle.clone())
+                && polynomial.at(-1).unwrap() == generate_first(triangle)
+        }
+        QuickCheck::new().tests(1000).quickcheck(prop as fn(Vec<i16>) -> bool);
+    }
+
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample09.txt");
+        let res = read_from_v2("src/input/sample09.txt").unwrap();
         assert_eq!(res, 2);
     }
 
     #[test]
     fn test_part_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day09.txt").unwrap();
         assert_eq!(res, 1057);
     }
 }
\ No newline at end of file
@@ -1,89 +1,72 @@
-use itertools::Itertools;
-
+use crate::error::AocError;
+use crate::geometry::{self, Direction, Point};
+use crate::grid::Grid;
 use crate::helpers;
+use crate::solution::Solution;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Parsed = Grid<Option<PipeShape>>;
+
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn title(&self) -> &'static str {
+        "Pipe Maze"
+    }
+
+    fn parse(&self, input: &str) -> Result<Grid<Option<PipeShape>>, AocError> {
+        Grid::parse(10, input, char_to_pipe_shape)
+    }
 
+    fn part_1(&self, pipe_map: &Grid<Option<PipeShape>>) -> Result<i64, AocError> {
+        let path = find_loop(pipe_map)?;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day10.txt")
+        Ok((path.len() / 2) as i64)
+    }
+
+    fn part_2(&self, pipe_map: &Grid<Option<PipeShape>>) -> Result<i64, AocError> {
+        let path = find_loop(pipe_map)?;
+        // every tile of the loop is on its border
+        let double_area = geometry::shoelace_double_area(&path);
+        Ok(geometry::pick_interior_points(double_area, path.len() as i64))
+    }
 }
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day10.txt")
+
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day10.part_1(&Day10.parse(input)?)
 }
 
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
+}
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let pipe_map: Vec<Vec<Option<PipeShape>>> = sample.iter()
-        .map(|line| parse_line(line).iter().map(|&c| char_to_pipe_shape(c)).collect())
-        .collect();
-
-    let start = get_starting_position(&pipe_map);
-    let mut path: Vec<(i64, i64)> = vec![];
-    // try North
-    let loop_starting_north = follow_path_until_loop(start, &pipe_map, Direction::North);
-    if let Some(north) = loop_starting_north {
-        path = north;
-    } else {
-        // try East
-        let loop_starting_east = follow_path_until_loop(start, &pipe_map, Direction::East);
-        if let Some(east) = loop_starting_east {
-            path = east;
-        } else {
-            let loop_starting_south = follow_path_until_loop(start, &pipe_map, Direction::South);
-            if let Some(south) = loop_starting_south {
-                path = south;
-            }
-        }
-    }
-    // No need to try West as we cannot fail for 3/4, we need at least two entrypoint
 
-    (path.len() / 2) as i64
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day10.part_2(&Day10.parse(input)?)
 }
 
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
+}
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let pipe_map: Vec<Vec<Option<PipeShape>>> = sample.iter()
-        .map(|line| parse_line(line).iter().map(|&c| char_to_pipe_shape(c)).collect())
-        .collect();
-
-    let start = get_starting_position(&pipe_map);
-    let mut path: Vec<(i64, i64)> = vec![];
-    // try North
-    let loop_starting_north = follow_path_until_loop(start, &pipe_map, Direction::North);
-    if let Some(north) = loop_starting_north {
-        path = north;
-    } else {
-        // try East
-        let loop_starting_east = follow_path_until_loop(start, &pipe_map, Direction::East);
-        if let Some(east) = loop_starting_east {
-            path = east;
-        } else {
-            let loop_starting_south = follow_path_until_loop(start, &pipe_map, Direction::South);
-            if let Some(south) = loop_starting_south {
-                path = south;
-            }
-        }
-    }
+
+fn find_loop(pipe_map: &Grid<Option<PipeShape>>) -> Result<Vec<Point>, AocError> {
+    let start = get_starting_position(pipe_map)
+        .ok_or_else(|| AocError::unsolvable(10, "there is no starting position"))?;
+    // try North, then East, then South
     // No need to try West as we cannot fail for 3/4, we need at least two entrypoint
-    let mut path_loop = path.clone();
-    path_loop.push(*path_loop.first().unwrap());
-    // Using Gauss area theorem to get the area of the path
-    let area: i64 = path_loop.clone().iter().tuple_windows().map(|(&u, &v)| shoelace_multiplication(u, v)).sum::<i64>() / 2;
-    let nb_nodes = path.len() as i64;
-
-    // Using Pick theorem to get the number of nodes contained within the area
-    let inner_nodes = area.abs() - (nb_nodes / 2) + 1;
-    inner_nodes
+    [Direction::North, Direction::East, Direction::South].into_iter()
+        .find_map(|direction| follow_path_until_loop(start, pipe_map, direction))
+        .ok_or_else(|| AocError::unsolvable(10, "no loop goes through the starting position"))
 }
 
-
 // Option<PipeShape> => None for '.'
 #[derive(Debug, PartialEq, Eq)]
-enum PipeShape {
+pub enum PipeShape {
     SouthNorth,
     SouthEast,
     SouthWest,
@@ -93,10 +76,6 @@ enum PipeShape {
     Start,
 }
 
-fn parse_line(input: &str) -> Vec<char> {
-    input.chars().collect()
-}
-
 fn char_to_pipe_shape(pipe: char) -> Option<PipeShape> {
     match pipe {
         '|' => Some(PipeShape::SouthNorth),
@@ -110,150 +89,45 @@ fn char_to_pipe_shape(pipe: char) -> Option<PipeShape> {
     }
 }
 
-fn get_starting_position(pipes: &[Vec<Option<PipeShape>>]) -> (i64, i64) {
-    let res = pipes.iter().enumerate().find_map(|(y_idx, line)| {
-        let find_pos: Option<(usize, &Option<PipeShape>)> = line.iter().find_position(|&pipe| matches!(pipe, Some(PipeShape::Start)));
-        match find_pos {
-            Some((x_idx, _)) => Some((y_idx as i64, x_idx as i64)),
-            _ => None
+impl PipeShape {
+    // the two directions this pipe leads to, `None` for the starting position
+    fn connections(&self) -> Option<[Direction; 2]> {
+        match self {
+            PipeShape::SouthNorth => Some([Direction::South, Direction::North]),
+            PipeShape::SouthEast => Some([Direction::South, Direction::East]),
+            PipeShape::SouthWest => Some([Direction::South, Direction::West]),
+            PipeShape::EastWest => Some([Direction::East, Direction::West]),
+            PipeShape::NorthEast => Some([Direction::North, Direction::East]),
+            PipeShape::NorthWest => Some([Direction::North, Direction::West]),
+            PipeShape::Start => None,
         }
-    });
-    let (y_pos, x_pos) = res.unwrap();
-
-    (y_pos, x_pos)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
+fn get_starting_position(pipes: &Grid<Option<PipeShape>>) -> Option<Point> {
+    pipes.position(|pipe| matches!(pipe, Some(PipeShape::Start))).map(Point::from)
 }
 
-fn follow_path_until_loop((initial_y, initial_x): (i64, i64), pipes: &Vec<Vec<Option<PipeShape>>>, original_direction: Direction) -> Option<Vec<(i64, i64)>> {
-    let max_x = pipes.first().unwrap().len() as i64;
-    let max_y = pipes.len() as i64;
-    let mut acc = vec![(initial_y, initial_x)];
-
-    let mut new_pos_x = initial_x;
-    let mut new_pos_y = initial_y;
-    let mut next_item_at_position: &Option<PipeShape> = &None; // we dont know at start what the next node is gonna be
+fn follow_path_until_loop(start: Point, pipes: &Grid<Option<PipeShape>>, original_direction: Direction) -> Option<Vec<Point>> {
+    let mut acc = vec![start];
+    let mut position = start;
     let mut direction = original_direction;
 
-    while *next_item_at_position != Some(PipeShape::Start) {
-        match direction {
-            Direction::North => {
-                new_pos_y = new_pos_y - 1;
-                if new_pos_y >= 0 {
-                    let next = (new_pos_y, new_pos_x);
-                    let next_item = pipes.get(new_pos_y as usize).unwrap().get(new_pos_x as usize).unwrap();
-                    next_item_at_position = next_item;
-                    match next_item {
-                        Some(PipeShape::SouthNorth) => {
-                            acc.push(next);
-                            direction = Direction::North;
-                        }
-                        Some(PipeShape::SouthWest) => {
-                            acc.push(next);
-                            direction = Direction::West;
-                        }
-                        Some(PipeShape::SouthEast) => {
-                            acc.push(next);
-                            direction = Direction::East;
-                        }
-                        Some(PipeShape::Start) => { return Some(acc.clone()); }
-                        _ => { return None; }
-                    }
-                } else {
-                    return None;
-                }
-            }
-            Direction::South => {
-                new_pos_y = new_pos_y + 1;
-                if new_pos_y < max_y {
-                    let next = (new_pos_y, new_pos_x);
-                    let next_item = pipes.get(new_pos_y as usize).unwrap().get(new_pos_x as usize).unwrap();
-                    next_item_at_position = next_item;
-                    match next_item {
-                        Some(PipeShape::SouthNorth) => {
-                            acc.push(next);
-                            direction = Direction::South;
-                        }
-                        Some(PipeShape::NorthEast) => {
-                            acc.push(next);
-                            direction = Direction::East;
-                        }
-                        Some(PipeShape::NorthWest) => {
-                            acc.push(next);
-                            direction = Direction::West;
-                        }
-                        Some(PipeShape::Start) => { return Some(acc.clone()); }
-                        _ => { return None; }
-                    }
-                } else {
-                    return None;
-                }
-            }
-            Direction::West => {
-                new_pos_x = new_pos_x - 1;
-                if new_pos_x >= 0 {
-                    let next = (new_pos_y, new_pos_x);
-                    let next_item = pipes.get(new_pos_y as usize).unwrap().get(new_pos_x as usize).unwrap();
-                    next_item_at_position = next_item;
-                    match next_item {
-                        Some(PipeShape::EastWest) => {
-                            acc.push(next);
-                            direction = Direction::West;
-                        }
-                        Some(PipeShape::NorthEast) => {
-                            acc.push(next);
-                            direction = Direction::North;
-                        }
-                        Some(PipeShape::SouthEast) => {
-                            acc.push(next);
-                            direction = Direction::South;
-                        }
-                        Some(PipeShape::Start) => { return Some(acc.clone()); }
-                        _ => { return None; }
-                    }
-                } else {
-                    return None;
-                }
-            }
-            Direction::East => {
-                new_pos_x = new_pos_x + 1;
-                if new_pos_x < max_x {
-                    let next = (new_pos_y, new_pos_x);
-                    let next_item = pipes.get(new_pos_y as usize).unwrap().get(new_pos_x as usize).unwrap();
-                    next_item_at_position = next_item;
-                    match next_item {
-                        Some(PipeShape::EastWest) => {
-                            acc.push(next);
-                            direction = Direction::East;
-                        }
-                        Some(PipeShape::NorthWest) => {
-                            acc.push(next);
-                            direction = Direction::North;
-                        }
-                        Some(PipeShape::SouthWest) => {
-                            acc.push(next);
-                            direction = Direction::South;
-                        }
-                        Some(PipeShape::Start) => { return Some(acc.clone()); }
-                        _ => { return None; }
-                    }
-                } else {
-                    return None;
-                }
-            }
+    loop {
+        position = position.step(direction);
+        let pipe = pipes.get(position.x, position.y)?.as_ref()?;
+        let Some([a, b]) = pipe.connections() else {
+            return Some(acc);
         };
+        // the pipe must connect back to where we come from
+        let from = direction.opposite();
+        direction = match (a == from, b == from) {
+            (true, _) => b,
+            (_, true) => a,
+            _ => return None,
+        };
+        acc.push(position);
     }
-    None
-}
-
-fn shoelace_multiplication((vy, vx): (i64, i64), (uy, ux): (i64, i64)) -> i64 {
-    vx * uy - vy * ux
 }
 
 #[cfg(test)]
@@ -262,22 +136,22 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let res = part_1();
+        let res = read_from("src/input/day10.txt").unwrap();
         assert_eq!(res, 7066);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample10.txt");
+        let res = read_from("src/input/sample10.txt").unwrap();
         assert_eq!(res, 8);
-        let res = read_from("src/input/sample10b.txt");
+        let res = read_from("src/input/sample10b.txt").unwrap();
         assert_eq!(res, 4);
     }
 
     #[test]
-    fn test_parse_line() {
-        let res = parse_line(".L-F.");
-        assert_eq!(res, vec!['.', 'L', '-', 'F', '.']);
+    fn test_parse() {
+        let res = Day10.parse(".L-F.").unwrap();
+        assert_eq!(res.row(0), &[None, Some(PipeShape::NorthEast), Some(PipeShape::EastWest), Some(PipeShape::SouthEast), None]);
     }
 
     #[test]
@@ -294,35 +168,40 @@ mod tests {
 
     #[test]
     fn test_get_starting_position() {
-        let sample = helpers::read("src/input/sample10.txt").unwrap();
-        let pipe_map: Vec<Vec<Option<PipeShape>>> = sample.iter()
-            .map(|line| parse_line(line).iter().map(|&c| char_to_pipe_shape(c)).collect())
-            .collect();
+        let pipe_map = Day10.parse(&helpers::read_to_string("src/input/sample10.txt").unwrap()).unwrap();
         let res = get_starting_position(&pipe_map);
-        assert_eq!(res, (2, 0));
+        assert_eq!(res, Some(Point::new(0, 2)));
+    }
+
+    #[test]
+    fn test_no_loop() {
+        let err = solve(".....\n.S-7.\n.|...\n.L-J.\n.....").unwrap_err();
+        assert_eq!(err.to_string(), "day 10: cannot solve this input: no loop goes through the starting position");
+        let err = solve("..F7.\n.FJ|.").unwrap_err();
+        assert_eq!(err.to_string(), "day 10: cannot solve this input: there is no starting position");
     }
 
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample10c.txt");
+        let res = read_from_v2("src/input/sample10c.txt").unwrap();
         assert_eq!(res, 4);
     }
 
     #[test]
     fn test_read_from_v2_b() {
-        let res = read_from_v2("src/input/sample10d.txt");
+        let res = read_from_v2("src/input/sample10d.txt").unwrap();
         assert_eq!(res, 28);
     }
 
     #[test]
     fn test_read_from_v2_c() {
-        let res = read_from_v2("src/input/sample10e.txt");
+        let res = read_from_v2("src/input/sample10e.txt").unwrap();
         assert_eq!(res, 9);
     }
 
     #[test]
     fn test_part_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day10.txt").unwrap();
         assert_eq!(res, 401);
     }
 }
\ No newline at end of file
@@ -1,157 +1,168 @@
-use itertools::Itertools;
-use nom::bytes::complete::tag;
+use nom::bytes::complete::{tag, take_while1};
 use nom::character::complete;
-use nom::error::Error;
-use nom::multi::{separated_list0};
-use nom::Parser;
+use nom::character::complete::space1;
+use nom::IResult;
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
+use crate::error::AocError;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day12.txt")
-}
+pub struct Day12;
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day12.txt")
-}
+impl Solution for Day12 {
+    type Parsed = Vec<(String, Vec<i64>)>;
 
+    fn day(&self) -> u8 {
+        12
+    }
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let records: Vec<(&str, Vec<i64>)> = sample.iter().map(|line| parse_line(line)).collect();
+    fn title(&self) -> &'static str {
+        "Hot Springs"
+    }
 
-    let res = records.into_iter().map(|(record, checks)| get_num_sol(record, checks)).sum();
+    fn parse(&self, input: &str) -> Result<Vec<(String, Vec<i64>)>, AocError> {
+        helpers::lines(input).iter().enumerate()
+            .map(|(idx, line)| {
+                let (record, checks) = helpers::parse_all(12, idx, line, parse_line)?;
+                Ok((record.to_string(), checks))
+            })
+            .collect()
+    }
 
-    res
-}
+    fn part_1(&self, records: &Vec<(String, Vec<i64>)>) -> Result<i64, AocError> {
+        let res = records.iter().map(|(record, checks)| count_arrangements(record, checks)).sum();
 
-fn get_num_sol(record: &str, checks: Vec<i64>) -> i64 {
-    let mut storage = vec![record.to_string()];
-    for x in (0..(record.len())) {
-        if record.chars().nth(x).unwrap() == '?' {
-            let new_values: Vec<String> = storage.into_iter().flat_map(|item| {
-                let mut dot: String = item.clone();
-                dot.replace_range(x..(x + 1), ".");
-// println!("dot:{:?}", dot);
-                let mut hash: String = item.clone();
-                hash.replace_range(x..(x + 1), "#");
-// println!("hash:{:?}", hash);
-                vec![dot, hash]
-            }).collect();
-            storage = new_values;
-        }
+        Ok(res)
     }
 
-// println!("{:?}", storage);
+    fn part_2(&self, records: &Vec<(String, Vec<i64>)>) -> Result<i64, AocError> {
+        let records: Vec<(String, Vec<i64>)> = records.iter().map(|(record, checks)| unfold(record, checks)).collect();
 
-    let res = storage.into_iter().filter(|attempts| is_valid(attempts, checks.clone())).count() as i64;
-    res
-}
+        let res = records.iter().map(|(record, checks)| count_arrangements(record, checks)).sum();
 
-pub fn get_num_sol_v2(record: &str, checks: Vec<i64>) -> i64 {
-    let mut storage = vec![record.to_string()];
-    for x in (0..(record.len())) {
-        if record.chars().nth(x).unwrap() == '?' {
-            let new_values: Vec<String> = storage.into_iter().flat_map(|item| {
-                let mut dot: String = item.clone();
-                dot.replace_range(x..(x + 1), ".");
-                let mut hash: String = item.clone();
-                hash.replace_range(x..(x + 1), "#");
-                vec![dot, hash]
-            }).collect();
-            storage = new_values;
-        }
+        Ok(res)
     }
-
-    let res = storage.into_iter().filter(|attempts| is_valid(attempts, checks.clone())).count() as i64;
-    res
 }
 
-fn parse_line(input: &str) -> (&str, Vec<i64>) {
-    let (springs_part, input) = input.split_once(" ").unwrap();
-    let (_, count): (&str, Vec<i64>) = separated_list0(tag(","), complete::i64::<&str, Error<&str>>).parse(input).unwrap();
 
-    (springs_part, count)
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day12.part_1(&Day12.parse(input)?)
 }
 
-fn parse_line_v2(input: &str) -> (String, Vec<i64>) {
-    let (springs_part, input) = input.split_once(" ").unwrap();
-    let mut springs = springs_part.to_string();
-    springs.push_str("?");
-    let mut springs_complete = springs.repeat(5);
-    springs_complete.replace_range((springs.len() - 1)..springs.len(), "");
-    let (_, count): (&str, Vec<i64>) = separated_list0(tag(","), complete::i64::<&str, Error<&str>>).parse(input).unwrap();
-
-    let count_five_times = count.repeat(5);
-    (springs_complete, count_five_times)
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
 }
 
-
-fn is_valid(line: &str, groups: Vec<i64>) -> bool {
-    let spring_groups: Vec<&str> = line.split(".").filter(|&c| c != "").collect();
-    if spring_groups.len() == groups.len() {
-        spring_groups.into_iter().zip(groups.into_iter()).all(|(s, g)| s.len() as i64 == g)
-    } else {
-        false
-    }
+// Number of ways to replace the `?` so that the damaged springs form exactly the groups of `checks`
+pub fn count_arrangements(record: &str, checks: &[i64]) -> i64 {
+    let mut cache = vec![None; (record.len() + 1) * (checks.len() + 1)];
+    count_from(record.as_bytes(), checks, 0, 0, &mut cache)
 }
 
+// arrangements of `record[position..]` when the first `group` groups are placed,
+// `cache` holds the count of every `(position, group)` at `position * (checks.len() + 1) + group`
+fn count_from(record: &[u8], checks: &[i64], position: usize, group: usize, cache: &mut Vec<Option<i64>>) -> i64 {
+    if position >= record.len() {
+        return (group == checks.len()) as i64;
+    }
+    let key = position * (checks.len() + 1) + group;
+    if let Some(count) = cache[key] {
+        return count;
+    }
 
-fn is_not_valid(line: &str, groups: Vec<i64>) -> bool {
-    let spring_groups: Vec<&str> = line.split(".").filter(|&c| c != "").collect();
-    let is_false = spring_groups.into_iter()
-        .zip(groups.into_iter())
-        .all(|(s, g)| {
-            (s == "#".repeat(g as usize) && s.len() as i64 == g) || s.len() as i64 >= g
-        });
-
+    let spring = record[position];
+    let mut count = 0;
+    if spring == b'.' || spring == b'?' {
+        count += count_from(record, checks, position + 1, group, cache);
+    }
+    // the next group starts here: its springs can all be damaged and the one right after it is not
+    let size = checks.get(group).and_then(|&size| usize::try_from(size).ok()).filter(|&size| size > 0);
+    if let Some(size) = size.filter(|_| spring == b'#' || spring == b'?') {
+        let end = position + size;
+        let fits = end <= record.len() && !record[position..end].contains(&b'.') && record.get(end) != Some(&b'#');
+        if fits {
+            count += count_from(record, checks, end + 1, group + 1, cache);
+        }
+    }
 
-    is_false
+    cache[key] = Some(count);
+    count
 }
 
+fn parse_line(input: &str) -> IResult<&str, (&str, Vec<i64>)> {
+    separated_pair(
+        take_while1(|c| c == '.' || c == '#' || c == '?'),
+        space1,
+        separated_list1(tag(","), complete::i64),
+    )(input)
+}
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-    let records: Vec<(String, Vec<i64>)> = sample.iter().map(|line| parse_line_v2(line)).collect();
-
-    let res = records.into_iter().map(|(record, checks)| get_num_sol_v2(record.as_str(), checks)).sum();
+fn unfold(springs_part: &str, count: &[i64]) -> (String, Vec<i64>) {
+    let springs_complete = [springs_part; 5].join("?");
 
-    res
+    let count_five_times = count.repeat(5);
+    (springs_complete, count_five_times)
 }
 
-fn find_large_solution(line: String, records: Vec<i64>) -> (String, Vec<i64>) {
-    // let largest = records.into_iter().max().unwrap() as usize;
-    // let mut first_group = records.clone();
-    // let mut second_group = first_group.split_off(largest);
-    // let max = second_group.remove(0);
-    // let groups: Vec<String> = line.chars().group_by(|&c| c == '#' || c == '?')
-    //     .map(|(key, group)| group.collect()).map(|group| group.join("")).collect();
-
-    // for ".##.?######?.##. 1,7,1"
-    // find largest group of consecutive spring (7)
-    // find largest string that matches largest group (?######?)
-    // get combination for that group => 2
-    // get combination for sub-problems ".##. 1" and ".##. 1" => 2 and 2
-    // get final result 2*2*2 => 8
-
-    ("".to_string(), vec![])
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day12.part_2(&Day12.parse(input)?)
 }
 
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // brute force over every replacement of the `?`, only kept to check `count_arrangements` on small rows
+    fn get_num_sol(record: &str, checks: Vec<i64>) -> i64 {
+        let mut storage = vec![record.to_string()];
+        for x in 0..record.len() {
+            if record.chars().nth(x).unwrap() == '?' {
+                let new_values: Vec<String> = storage.into_iter().flat_map(|item| {
+                    let mut dot: String = item.clone();
+                    dot.replace_range(x..(x + 1), ".");
+                    let mut hash: String = item.clone();
+                    hash.replace_range(x..(x + 1), "#");
+                    vec![dot, hash]
+                }).collect();
+                storage = new_values;
+            }
+        }
+
+        storage.into_iter().filter(|attempts| is_valid(attempts, checks.clone())).count() as i64
+    }
+
+    fn is_valid(line: &str, groups: Vec<i64>) -> bool {
+        let spring_groups: Vec<&str> = line.split('.').filter(|c| !c.is_empty()).collect();
+        if spring_groups.len() == groups.len() {
+            spring_groups.into_iter().zip(groups).all(|(s, g)| s.len() as i64 == g)
+        } else {
+            false
+        }
+    }
+
+    fn is_not_valid(line: &str, groups: Vec<i64>) -> bool {
+        let spring_groups: Vec<&str> = line.split('.').filter(|c| !c.is_empty()).collect();
+        spring_groups.into_iter()
+            .zip(groups)
+            .all(|(s, g)| {
+                (s == "#".repeat(g as usize) && s.len() as i64 == g) || s.len() as i64 >= g
+            })
+    }
+
     #[test]
     fn test_part_1() {
-        let res = part_1();
+        let res = read_from("src/input/day12.txt").unwrap();
         assert_eq!(res, 7792);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample12.txt");
+        let res = read_from("src/input/sample12.txt").unwrap();
         assert_eq!(res, 21);
     }
 
@@ -165,23 +176,54 @@ mod tests {
         assert_eq!(get_num_sol("?###????????", vec![3, 2, 1]), 10);
     }
 
-    // #[test]
-    // fn test_get_num_sol_v2() {
-    // assert_eq!(get_num_sol_v2("???.###????.###????.###????.###????.###", vec![1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3]), 1);
-    // assert_eq!(get_num_sol_v2(".??..??...?##.?.??..??...?##.?.??..??...?##.?.??..??...?##.?.??..??...?##.", vec![1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3]), 16384);
-    // }
+    #[test]
+    fn test_count_arrangements() {
+        assert_eq!(count_arrangements("???.###", &[1, 1, 3]), 1);
+        assert_eq!(count_arrangements(".??..??...?##.", &[1, 1, 3]), 4);
+        assert_eq!(count_arrangements("?###????????", &[3, 2, 1]), 10);
+        assert_eq!(count_arrangements("###", &[]), 0);
+        assert_eq!(count_arrangements("..?", &[]), 1);
+        assert_eq!(count_arrangements("???.###????.###????.###????.###????.###", &[1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3]), 1);
+        assert_eq!(count_arrangements(".??..??...?##.?.??..??...?##.?.??..??...?##.?.??..??...?##.?.??..??...?##.", &[1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3]), 16384);
+    }
+
+    #[test]
+    fn test_count_arrangements_against_brute_force() {
+        let records = Day12.parse(&helpers::read_to_string("src/input/day12.txt").unwrap()).unwrap();
+        let small = records.iter().filter(|(record, _)| record.matches('?').count() <= 12);
+        for (record, checks) in small {
+            assert_eq!(count_arrangements(record, checks), get_num_sol(record, checks.clone()), "{} {:?}", record, checks);
+        }
+    }
 
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample12.txt");
+        let res = read_from_v2("src/input/sample12.txt").unwrap();
         assert_eq!(res, 525152);
     }
 
     #[test]
     fn test_parse_line() {
-        let res = parse_line(".??..??...?##. 1,1,3");
+        let (_, res) = parse_line(".??..??...?##. 1,1,3").unwrap();
         assert_eq!(res.0, ".??..??...?##.");
         assert_eq!(res.1, vec![1, 1, 3]);
+        assert!(parse_line(".??..??...?##.").is_err());
+    }
+
+    #[test]
+    fn test_unfold() {
+        assert_eq!(unfold(".#", &[1]), (".#?.#?.#?.#?.#".to_string(), vec![1, 1, 1, 1, 1]));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        match Day12.parse("???.### 1,1,3\n.??..??...?##. 1,x,3") {
+            Err(AocError::Parse { line, column, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(column, 17);
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
     }
 
     #[test]
@@ -190,7 +232,7 @@ mod tests {
         let mut s = "ABCD!1234".to_string();
         s.replace_range(4..5, "_");
         let mut storage = vec![sample.clone()];
-        for x in (0..(sample.len())) {
+        for x in 0..sample.len() {
             if sample.chars().nth(x).unwrap() == '?' {
                 let new_values: Vec<String> = storage.into_iter().flat_map(|item| {
                     let mut dot: String = item.clone();
@@ -228,7 +270,7 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        let res = part_2();
-        assert_eq!(res, 0);
+        let res = read_from_v2("src/input/day12.txt").unwrap();
+        assert_eq!(res, 13012052341533);
     }
 }
\ No newline at end of file
@@ -3,38 +3,71 @@ use find_all::FindAll;
 
 use itertools::Itertools;
 
+use crate::error::AocError;
+use crate::grid::Grid;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day13.txt")
-}
+pub struct Day13;
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day13.txt")
-}
+impl Solution for Day13 {
+    type Parsed = Vec<Grid<char>>;
 
+    fn day(&self) -> u8 {
+        13
+    }
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
+    fn title(&self) -> &'static str {
+        "Point of Incidence"
+    }
 
-    let splitted = sample.into_iter()
-        .group_by(|k| k != "")
-        .into_iter()
-        .filter(|(key, group)| *key)
-        .map(|(_, group)| group.collect::<Vec<String>>())
-        .collect::<Vec<Vec<String>>>();
+    fn parse(&self, input: &str) -> Result<Vec<Grid<char>>, AocError> {
+        let lines = helpers::lines(input);
+        let mut splitted: Vec<Grid<char>> = vec![];
+        let mut start = 0;
+        // a blank line closes a pattern, and so does the end of the input
+        for end in (0..lines.len()).filter(|&idx| lines[idx].is_empty()).chain([lines.len()]) {
+            if end > start {
+                let pattern = Grid::parse(13, &lines[start..end].join("\n"), |c| c).map_err(|e| e.shift_lines(start))?;
+                splitted.push(pattern);
+            }
+            start = end + 1;
+        }
+        Ok(splitted)
+    }
 
+    fn part_1(&self, splitted: &Vec<Grid<char>>) -> Result<i64, AocError> {
 
-    let res = splitted.into_iter().map(|v| {
-        match get_summary_h_mirror(v.clone()) {
-            0 => get_summary_v_mirror(v) as i64,
-            i => i as i64
-        }
-    }).sum();
+        let res = splitted.iter().map(|v| {
+            match get_summary_h_mirror(v) {
+                0 => get_summary_v_mirror(v) as i64,
+                i => i as i64
+            }
+        }).sum();
+
+        Ok(res)
+    }
+
+    fn part_2(&self, splitted: &Vec<Grid<char>>) -> Result<i64, AocError> {
 
-    res
+        let res = splitted.iter().map(|v| {
+            match get_summary_h_mirror_v2(v) {
+                0 => get_summary_v_mirror_v2(v) as i64,
+                i => i as i64
+            }
+        }).sum();
+
+        Ok(res)
+    }
+}
+
+
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day13.part_1(&Day13.parse(input)?)
+}
+
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
 }
 
 fn get_mirror_index(notes: Vec<String>) -> Vec<usize> {
@@ -63,13 +96,11 @@ fn is_mirror(notes: Vec<String>, index: usize) -> bool {
     if index > notes.len() {
         false
     } else {
-        let test = (0..max_checks).into_iter().all(|i| {
+        (0..max_checks).all(|i| {
             let l1 = notes.get(index - i).unwrap();
             let l2 = notes.get(index + i + 1).unwrap();
-            let ch = l1 == l2;
-            ch
-        });
-        test
+            l1 == l2
+        })
     }
 }
 
@@ -94,10 +125,11 @@ fn is_mirror_v2(notes: Vec<String>, index: usize) -> bool {
     }
 }
 
-fn get_summary_h_mirror(notes: Vec<String>) -> usize {
+fn get_summary_h_mirror(notes: &Grid<char>) -> usize {
+    let notes = notes.lines();
     // FIXME: change to & instead of clone
     let indexes = get_mirror_index(notes.clone());
-    let n = indexes.into_iter().find_map(|index| if is_mirror(notes.clone(), index) { Some(index) } else { None });
+    let n = indexes.into_iter().find(|&index| is_mirror(notes.clone(), index));
 
     if let Some(i) = n {
         (i + 1) * 100
@@ -106,20 +138,12 @@ fn get_summary_h_mirror(notes: Vec<String>) -> usize {
     }
 }
 
-fn get_summary_v_mirror(notes: Vec<String>) -> usize {
-    // flip matrix
-    let notes_chars = notes.iter().map(|line| line.chars().collect::<Vec<char>>()).collect::<Vec<Vec<char>>>();
-    let size = notes_chars.first().unwrap().len();
-    let flipped: Vec<Vec<char>> = (0..size).map(|i| notes_chars.clone()
-        .into_iter()
-        .map(|c| c.get(i).unwrap().clone())
-        .collect()
-    ).collect();
-    let collapsed = flipped.iter().map(|v| v.into_iter().collect::<String>()).collect::<Vec<String>>();
+fn get_summary_v_mirror(notes: &Grid<char>) -> usize {
+    let collapsed = notes.transpose().lines();
 
     // FIXME: change to & instead of clone
     let indexes = get_mirror_index(collapsed.clone());
-    let n = indexes.into_iter().find_map(|index| if is_mirror(collapsed.clone(), index) { Some(index) } else { None });
+    let n = indexes.into_iter().find(|&index| is_mirror(collapsed.clone(), index));
 
     match n {
         Some(i) => i + 1,
@@ -127,10 +151,11 @@ fn get_summary_v_mirror(notes: Vec<String>) -> usize {
     }
 }
 
-fn get_summary_h_mirror_v2(notes: Vec<String>) -> usize {
+fn get_summary_h_mirror_v2(notes: &Grid<char>) -> usize {
+    let notes = notes.lines();
     // FIXME: change to & instead of clone
     let indexes = get_mirror_index_v2(notes.clone());
-    let n = indexes.into_iter().find_map(|index| if is_mirror_v2(notes.clone(), index) { Some(index) } else { None });
+    let n = indexes.into_iter().find(|&index| is_mirror_v2(notes.clone(), index));
 
     if let Some(i) = n {
         (i + 1) * 100
@@ -139,20 +164,12 @@ fn get_summary_h_mirror_v2(notes: Vec<String>) -> usize {
     }
 }
 
-fn get_summary_v_mirror_v2(notes: Vec<String>) -> usize {
-    // flip matrix
-    let notes_chars = notes.iter().map(|line| line.chars().collect::<Vec<char>>()).collect::<Vec<Vec<char>>>();
-    let size = notes_chars.first().unwrap().len();
-    let flipped: Vec<Vec<char>> = (0..size).map(|i| notes_chars.clone()
-        .into_iter()
-        .map(|c| c.get(i).unwrap().clone())
-        .collect()
-    ).collect();
-    let collapsed = flipped.iter().map(|v| v.into_iter().collect::<String>()).collect::<Vec<String>>();
+fn get_summary_v_mirror_v2(notes: &Grid<char>) -> usize {
+    let collapsed = notes.transpose().lines();
 
     // FIXME: change to & instead of clone
     let indexes = get_mirror_index_v2(collapsed.clone());
-    let n = indexes.into_iter().find_map(|index| if is_mirror_v2(collapsed.clone(), index) { Some(index) } else { None });
+    let n = indexes.into_iter().find(|&index| is_mirror_v2(collapsed.clone(), index));
 
     match n {
         Some(i) => {
@@ -163,25 +180,12 @@ fn get_summary_v_mirror_v2(notes: Vec<String>) -> usize {
 }
 
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-
-    let splitted = sample.into_iter()
-        .group_by(|k| k != "")
-        .into_iter()
-        .filter(|(key, group)| *key)
-        .map(|(_, group)| group.collect::<Vec<String>>())
-        .collect::<Vec<Vec<String>>>();
-
-
-    let res = splitted.into_iter().map(|v| {
-        match get_summary_h_mirror_v2(v.clone()) {
-            0 => get_summary_v_mirror_v2(v) as i64,
-            i => i as i64
-        }
-    }).sum();
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day13.part_2(&Day13.parse(input)?)
+}
 
-    res
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 
@@ -191,17 +195,17 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let res = part_1();
+        let res = read_from("src/input/day13.txt").unwrap();
         assert_eq!(res, 37381);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample13.txt");
+        let res = read_from("src/input/sample13.txt").unwrap();
         assert_eq!(res, 405);
-        let res = read_from("src/input/sample13b.txt");
+        let res = read_from("src/input/sample13b.txt").unwrap();
         assert_eq!(res, 100);
-        let res = read_from("src/input/sample13c.txt");
+        let res = read_from("src/input/sample13c.txt").unwrap();
         assert_eq!(res, 3);
     }
 
@@ -210,7 +214,7 @@ mod tests {
         let res = get_mirror_index(vec!["aabb".to_string(), "bbaa".to_string(), "abcd".to_string(), "abcd".to_string(), "bbaa".to_string()]);
         assert_eq!(res, vec![2]);
         let res = get_mirror_index(vec!["aabb".to_string(), "bbaa".to_string(), "abcd".to_string(), "abdd".to_string(), "bbaa".to_string()]);
-        assert_eq!(res, vec![]);
+        assert_eq!(res, Vec::<usize>::new());
     }
 
     #[test]
@@ -220,7 +224,7 @@ mod tests {
         let res = get_mirror_index_v2(vec!["aabb".to_string(), "bbaa".to_string(), "abcd".to_string(), "abdd".to_string(), "bbaa".to_string()]);
         assert_eq!(res, vec![2]);
         let res = get_mirror_index_v2(vec!["aabb".to_string(), "bbaa".to_string(), "abcd".to_string(), "abdh".to_string(), "bbaa".to_string()]);
-        assert_eq!(res, vec![]);
+        assert_eq!(res, Vec::<usize>::new());
     }
 
     #[test]
@@ -245,27 +249,37 @@ mod tests {
 
     #[test]
     fn test_get_summary_h_mirror() {
-        let res = get_summary_h_mirror(vec!["aabb".to_string(), "bbaa".to_string(), "abcd".to_string(), "abcd".to_string(), "bbaa".to_string()]);
+        let res = get_summary_h_mirror(&Grid::parse(13, "aabb\nbbaa\nabcd\nabcd\nbbaa", |c| c).unwrap());
         assert_eq!(res, 300);
     }
 
     #[test]
     fn test_get_summary_v_mirror() {
-        let res = get_summary_v_mirror(vec!["cabba".to_string(), "dbbbb".to_string(), "cadda".to_string(), "aabba".to_string(), "bbaab".to_string()]);
+        let res = get_summary_v_mirror(&Grid::parse(13, "cabba\ndbbbb\ncadda\naabba\nbbaab", |c| c).unwrap());
         assert_eq!(res, 3);
     }
 
+    #[test]
+    fn test_uneven_pattern() {
+        match Day13.parse("#.#\n.#.\n\n##\n#.#") {
+            Err(AocError::Parse { line, .. }) => assert_eq!(line, 5),
+            res => panic!("unexpected result {:?}", res),
+        }
+        let patterns = Day13.parse("é.\né.").unwrap();
+        assert_eq!(patterns[0].width(), 2);
+    }
+
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample13.txt");
+        let res = read_from_v2("src/input/sample13.txt").unwrap();
         assert_eq!(res, 400);
-        let res = read_from_v2("src/input/sample13c.txt");
+        let res = read_from_v2("src/input/sample13c.txt").unwrap();
         assert_eq!(res, 10);
     }
 
     #[test]
     fn test_part_2() {
-        let res = part_2();
+        let res = read_from_v2("src/input/day13.txt").unwrap();
         assert_eq!(res, 28210);
     }
 }
\ No newline at end of file
@@ -1,88 +1,118 @@
-use itertools::Itertools;
+use crate::cycle;
+use crate::error::AocError;
+use crate::geometry::Direction;
+use crate::grid::Grid;
 use crate::helpers;
+use crate::solution::Solution;
 
-#[allow(dead_code)]
-pub fn part_1() -> i64 {
-    read_from("src/input/day14.txt")
-}
+pub struct Day14;
 
-#[allow(dead_code)]
-pub fn part_2() -> i64 {
-    read_from_v2("src/input/day14.txt")
-}
+impl Solution for Day14 {
+    type Parsed = Grid<char>;
 
+    fn day(&self) -> u8 {
+        14
+    }
 
-fn read_from(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
+    fn title(&self) -> &'static str {
+        "Parabolic Reflector Dish"
+    }
 
-    let flipped: Vec<String> = flip(&sample);
+    fn parse(&self, input: &str) -> Result<Grid<char>, AocError> {
+        Grid::parse(14, input, |c| c)
+    }
 
-    let new_layout: Vec<String> = flipped.iter().map(|line| slide_rock(line)).collect();
+    fn part_1(&self, sample: &Grid<char>) -> Result<i64, AocError> {
 
-    let flipped_back: Vec<String> = flip(&new_layout);
+        let mut new_layout: Grid<char> = sample.clone();
+        tilt(&mut new_layout, Direction::North);
 
-    let res = count_load(&flipped_back);
+        let res = count_load(&new_layout);
 
-    res as i64
-}
+        Ok(res as i64)
+    }
 
-fn slide_rock(source: &String) -> String {
-    let sections = source.split("#");
-    let moved_rocks: Vec<String> = sections.map(|v| v.chars().sorted().rev().join("")).collect();
-    moved_rocks.join("#")
-}
+    fn part_2(&self, sample: &Grid<char>) -> Result<i64, AocError> {
 
-fn flip(source: &Vec<String>) -> Vec<String> {
-    let source_chars: Vec<Vec<char>> = source.iter().map(|line| line.chars().collect::<Vec<char>>()).collect();
-    let size = source_chars.len();
+        let new_layout: Grid<char> = cycle::nth_state(sample.clone(), spin_cycle, SPIN_CYCLES);
 
-    let flipped: Vec<String> = (0..size).map(|i| source_chars.clone()
-        .into_iter()
-        .map(|c| c.get(i).unwrap().clone())
-        .collect()
-    ).map(|v: Vec<char>| v.iter().collect::<String>()).collect();
+        let res = count_load(&new_layout);
 
-    flipped
+        Ok(res as i64)
+    }
 }
 
 
-fn count_load(beams: &Vec<String>) -> usize {
-    beams.iter()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (index, line)| {
-            acc + line.matches("O").count() * (index + 1)
-        })
+pub fn solve(input: &str) -> Result<i64, AocError> {
+    Day14.part_1(&Day14.parse(input)?)
 }
 
+pub fn read_from(filepath: &str) -> Result<i64, AocError> {
+    solve(&helpers::read_to_string(filepath)?)
+}
 
-fn read_from_v2(filepath: &str) -> i64 {
-    let sample = helpers::read(filepath).unwrap();
-
-    let mut previous_layouts: Vec<Vec<String>> = vec![];
-    let mut iterations =  0;
-
-    // N
-    let mut flipped: Vec<String> = flip(&sample);
-    let mut new_layout: Vec<String> = flipped.iter().map(|line| slide_rock(line)).collect();
-    // while previous_layouts.find(|e|)
-
+// roll every rounded rock towards `direction` until it hits a cube rock, another rounded rock or the edge.
+// Each line along `direction` is walked once from the edge the rocks roll to.
+fn tilt(platform: &mut Grid<char>, direction: Direction) {
+    let (width, height) = (platform.width() as i64, platform.height() as i64);
+    let (lines, length) = match direction {
+        Direction::North | Direction::South => (width, height),
+        Direction::East | Direction::West => (height, width),
+    };
+    // the `i`-th cell of `line` counting from the edge
+    let position = |line: i64, i: i64| match direction {
+        Direction::North => (line, i),
+        Direction::South => (line, height - 1 - i),
+        Direction::West => (i, line),
+        Direction::East => (width - 1 - i, line),
+    };
+    for line in 0..lines {
+        // where the next rounded rock of this line stops
+        let mut free = 0;
+        for i in 0..length {
+            let (x, y) = position(line, i);
+            match platform.get(x, y) {
+                Some('#') => free = i + 1,
+                Some('O') => {
+                    if free != i {
+                        let (free_x, free_y) = position(line, free);
+                        *platform.get_mut(free_x, free_y).unwrap() = 'O';
+                        *platform.get_mut(x, y).unwrap() = '.';
+                    }
+                    free += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+}
 
-    // W
-    let mut flipped: Vec<String> = flip(&sample);
-    let mut new_layout: Vec<String> = flipped.iter().map(|line| slide_rock(line)).collect();
+const SPIN_CYCLES: usize = 1_000_000_000;
 
-    // flip back for west
-    // TODO: implement a flip_and_reverse
-    // flip back to north and reverse for SOUTH
-    // flip back to west and reverse for WEST
+fn spin_cycle(source: &Grid<char>) -> Grid<char> {
+    let mut platform = source.clone();
+    for direction in [Direction::North, Direction::West, Direction::South, Direction::East] {
+        tilt(&mut platform, direction);
+    }
+    platform
+}
 
+fn count_load(beams: &Grid<char>) -> usize {
+    beams.rows()
+        .rev()
+        .enumerate()
+        .fold(0, |acc, (index, line)| {
+            acc + line.iter().filter(|&&c| c == 'O').count() * (index + 1)
+        })
+}
 
-    let flipped_back: Vec<String> = flip(&new_layout);
 
-    let res = count_load(&flipped_back);
+pub fn solve_v2(input: &str) -> Result<i64, AocError> {
+    Day14.part_2(&Day14.parse(input)?)
+}
 
-    res as i64
+pub fn read_from_v2(filepath: &str) -> Result<i64, AocError> {
+    solve_v2(&helpers::read_to_string(filepath)?)
 }
 
 
@@ -93,19 +123,19 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let res = part_1();
+        let res = read_from("src/input/day14.txt").unwrap();
         assert_eq!(res, 105982);
     }
 
     #[test]
     fn test_read_from() {
-        let res = read_from("src/input/sample14.txt");
+        let res = read_from("src/input/sample14.txt").unwrap();
         assert_eq!(res, 136);
     }
 
     #[test]
     fn test_slide_rocks() {
-        let res = read_from("src/input/sample14.txt");
+        read_from("src/input/sample14.txt").unwrap();
 
         let s = ".O.#.OO".to_string();
         let t = s.split("#");
@@ -114,15 +144,50 @@ mod tests {
         assert_eq!(u, "O..#OO.");
     }
 
+    #[test]
+    fn test_tilt() {
+        let sample = Grid::parse(14, "O.#\n.O.\n..O", |c| c).unwrap();
+        let tilted = |direction| {
+            let mut platform = sample.clone();
+            tilt(&mut platform, direction);
+            platform.to_string()
+        };
+        assert_eq!(tilted(Direction::North), "OO#\n..O\n...");
+        assert_eq!(tilted(Direction::West), "O.#\nO..\nO..");
+        assert_eq!(tilted(Direction::South), "..#\n...\nOOO");
+        assert_eq!(tilted(Direction::East), ".O#\n..O\n..O");
+        let mut row = Grid::parse(14, ".O.#.OO", |c| c).unwrap();
+        tilt(&mut row, Direction::West);
+        assert_eq!(row.to_string(), "O..#OO.");
+    }
+
+    #[test]
+    fn test_spin_cycle() {
+        let sample = Day14.parse(&helpers::read_to_string("src/input/sample14.txt").unwrap()).unwrap();
+        let res = spin_cycle(&sample);
+        assert_eq!(res.to_string(), [
+            ".....#....",
+            "....#...O#",
+            "...OO##...",
+            ".OO#......",
+            ".....OOO#.",
+            ".O#...O#.#",
+            "....O#....",
+            "......OOOO",
+            "#...O###..",
+            "#..OO#....",
+        ].join("\n"));
+    }
+
     #[test]
     fn test_read_from_v2() {
-        let res = read_from_v2("src/input/sample14.txt");
-        assert_eq!(res, 0);
+        let res = read_from_v2("src/input/sample14.txt").unwrap();
+        assert_eq!(res, 64);
     }
 
     #[test]
     fn test_part_2() {
-        let res = part_2();
-        assert_eq!(res, 0);
+        let res = read_from_v2("src/input/day14.txt").unwrap();
+        assert_eq!(res, 85175);
     }
 }
\ No newline at end of file
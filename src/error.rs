@@ -0,0 +1,161 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum AocError {
+    Io(io::Error),
+    MissingInput {
+        name: String,
+        searched: Vec<PathBuf>,
+    },
+    /// `line` and `column` are 1-based, `snippet` is the whole offending line
+    Parse {
+        day: u8,
+        line: usize,
+        column: usize,
+        snippet: String,
+        message: String,
+    },
+    Unsolvable {
+        day: u8,
+        reason: String,
+    },
+    /// every day has exactly two parts
+    InvalidPart {
+        day: u8,
+        part: u8,
+    },
+    Fetch {
+        day: Option<u8>,
+        message: String,
+    },
+    InvalidAnswers {
+        message: String,
+    },
+    InvalidBaseline {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl AocError {
+    /// Locate `remaining`, the unparsed suffix of `text`, knowing that `text` starts at line `first_line`.
+    pub fn parse(day: u8, text: &str, first_line: usize, remaining: &str, message: impl Into<String>) -> AocError {
+        let offset = text.len().saturating_sub(remaining.len());
+        let consumed = &text[..offset];
+        let line_start = consumed.rfind('\n').map_or(0, |idx| idx + 1);
+        AocError::Parse {
+            day,
+            line: first_line + consumed.matches('\n').count(),
+            column: consumed[line_start..].chars().count() + 1,
+            snippet: text[line_start..].lines().next().unwrap_or("").to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn from_nom(day: u8, text: &str, first_line: usize, err: nom::Err<nom::error::Error<&str>>) -> AocError {
+        match err {
+            nom::Err::Incomplete(_) => AocError::parse(day, text, first_line, "", "unexpected end of input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                let message = format!("unexpected input ({} parser failed)", e.code.description());
+                AocError::parse(day, text, first_line, e.input, message)
+            }
+        }
+    }
+
+    /// Move a parse error `count` lines down, for a block parsed apart from the lines before it.
+    pub fn shift_lines(self, count: usize) -> AocError {
+        match self {
+            AocError::Parse { day, line, column, snippet, message } => AocError::Parse { day, line: line + count, column, snippet, message },
+            e => e,
+        }
+    }
+
+    pub fn unsolvable(day: u8, reason: impl Into<String>) -> AocError {
+        AocError::Unsolvable { day, reason: reason.into() }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::Io(e) => write!(f, "I/O error: {}", e),
+            AocError::MissingInput { name, searched } => {
+                write!(f, "no input named `{}`, looked for:", name)?;
+                for path in searched {
+                    write!(f, "\n    {}", path.display())?;
+                }
+                Ok(())
+            }
+            AocError::Parse { day, line, column, snippet, message } => {
+                writeln!(f, "day {:02}: parse error at line {}, column {}: {}", day, line, column, message)?;
+                writeln!(f, "    {}", snippet)?;
+                write!(f, "    {}^", " ".repeat(column - 1))
+            }
+            AocError::Unsolvable { day, reason } => write!(f, "day {:02}: cannot solve this input: {}", day, reason),
+            AocError::InvalidPart { day, part } => write!(f, "day {:02}: there is no part {}, only parts 1 and 2", day, part),
+            AocError::Fetch { day: Some(day), message } => write!(f, "day {:02}: could not fetch the input: {}", day, message),
+            AocError::Fetch { day: None, message } => write!(f, "could not fetch the input: {}", message),
+            AocError::InvalidAnswers { message } => write!(f, "invalid answers file: {}", message),
+            AocError::InvalidBaseline { path, message } => write!(f, "invalid benchmark baseline {}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(e: io::Error) -> Self {
+        AocError::Io(e)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use nom::bytes::complete::tag;
+    use nom::IResult;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_location() {
+        let text = "Game 1: 3 red\nGame 2: 4 purple";
+        let remaining = &text[22..];
+        match AocError::parse(2, text, 1, remaining, "unknown colour") {
+            AocError::Parse { day, line, column, snippet, message } => {
+                assert_eq!(day, 2);
+                assert_eq!(line, 2);
+                assert_eq!(column, 9);
+                assert_eq!(snippet, "Game 2: 4 purple");
+                assert_eq!(message, "unknown colour");
+            }
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_from_nom() {
+        let line = "Gmae 3";
+        let res: IResult<&str, &str> = tag("Game")(line);
+        let err = AocError::from_nom(2, line, 7, res.unwrap_err());
+        assert_eq!(
+            err.to_string(),
+            "day 02: parse error at line 7, column 1: unexpected input (Tag parser failed)\n    Gmae 3\n    ^"
+        );
+    }
+
+    #[test]
+    fn test_display_unsolvable() {
+        let err = AocError::unsolvable(10, "no starting position");
+        assert_eq!(err.to_string(), "day 10: cannot solve this input: no starting position");
+    }
+}
@@ -0,0 +1,207 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::AocError;
+use crate::input::input_name;
+
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2023";
+// adventofcode.com asks automated tools to throttle their requests
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(3);
+
+const USER_AGENT: &str = "github.com/thuchede/advent-of-code-2023 input fetcher";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+// Download puzzle inputs into `cache_dir`, an input that is already there is never requested again.
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+    cache_dir: PathBuf,
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, session: &str, cache_dir: PathBuf) -> Fetcher {
+        Fetcher {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            cache_dir,
+            interval: DEFAULT_INTERVAL,
+            last_request: None,
+        }
+    }
+
+    pub fn from_env(cache_dir: PathBuf) -> Result<Fetcher, AocError> {
+        let session = env::var(SESSION_VAR).ok().filter(|session| !session.is_empty())
+            .ok_or_else(|| AocError::Fetch { day: None, message: format!("set {} to your adventofcode.com session cookie", SESSION_VAR) })?;
+        let base_url = env::var(BASE_URL_VAR).ok().filter(|url| !url.is_empty());
+        Ok(Fetcher::new(base_url.as_deref().unwrap_or(DEFAULT_BASE_URL), &session, cache_dir))
+    }
+
+    pub fn with_base_url(self, base_url: &str) -> Fetcher {
+        Fetcher { base_url: base_url.trim_end_matches('/').to_string(), ..self }
+    }
+
+    // minimum delay between two requests
+    pub fn with_interval(self, interval: Duration) -> Fetcher {
+        Fetcher { interval, ..self }
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/day/{}/input", self.base_url, day)
+    }
+
+    pub fn fetch(&mut self, day: u8) -> Result<Fetched, AocError> {
+        let path = self.cache_dir.join(format!("{}.txt", input_name(day, None)));
+        if path.is_file() {
+            return Ok(Fetched::Cached(path));
+        }
+
+        if let Some(last_request) = self.last_request {
+            let elapsed = last_request.elapsed();
+            if elapsed < self.interval {
+                thread::sleep(self.interval - elapsed);
+            }
+        }
+        self.last_request = Some(Instant::now());
+
+        let to_error = |message: String| AocError::Fetch { day: Some(day), message };
+        let response = ureq::get(&self.url(day))
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|e| match e {
+                ureq::Error::Status(code, response) => to_error(format!("{} answered {} {}", response.get_url(), code, response.status_text())),
+                ureq::Error::Transport(transport) => to_error(transport.to_string()),
+            })?;
+        let content = response.into_string()?;
+        if content.trim().is_empty() {
+            return Err(to_error("the server sent an empty input".to_string()));
+        }
+
+        // write next to the destination first so an interrupted download is never mistaken for a cached input
+        fs::create_dir_all(&self.cache_dir)?;
+        let partial = path.with_extension("part");
+        fs::write(&partial, content)?;
+        fs::rename(&partial, &path)?;
+        Ok(Fetched::Downloaded(path))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    use crate::helpers::temp_dir;
+    use crate::input::InputResolver;
+
+    use super::*;
+
+    // answer every request with `status` and `body`, and record the request lines and cookies received
+    fn stub_server(status: &'static str, body: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/2023", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let received = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if line.starts_with("GET") || line.to_lowercase().starts_with("cookie") {
+                        request.push_str(line.trim_end());
+                        request.push('\n');
+                    }
+                }
+                received.lock().unwrap().push(request);
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let (url, requests) = stub_server("200 OK", "1abc2\n");
+        let dir = temp_dir("fetch-cache");
+        let mut fetcher = Fetcher::new(&url, "s3cr3t", dir.clone()).with_interval(Duration::ZERO);
+
+        assert_eq!(fetcher.fetch(1).unwrap(), Fetched::Downloaded(dir.join("day01.txt")));
+        assert_eq!(fs::read_to_string(dir.join("day01.txt")).unwrap(), "1abc2\n");
+        assert_eq!(fetcher.fetch(1).unwrap(), Fetched::Cached(dir.join("day01.txt")));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2023/day/1/input HTTP/1.1\n"));
+        assert!(requests[0].ends_with("session=s3cr3t\n"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error_is_not_cached() {
+        let (url, _) = stub_server("400 Bad Request", "Puzzle inputs differ by user.");
+        let dir = temp_dir("fetch-error");
+        let mut fetcher = Fetcher::new(&url, "expired", dir.clone()).with_interval(Duration::ZERO);
+
+        match fetcher.fetch(3) {
+            Err(AocError::Fetch { day, message }) => {
+                assert_eq!(day, Some(3));
+                assert!(message.ends_with("answered 400 Bad Request"), "{}", message);
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+        assert!(!dir.join("day03.txt").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let (url, requests) = stub_server("200 OK", "0 3 6 9 12 15\n");
+        let dir = temp_dir("fetch-rate");
+        let mut fetcher = Fetcher::new(&url, "s3cr3t", dir.clone()).with_interval(Duration::from_millis(300));
+
+        let start = Instant::now();
+        fetcher.fetch(8).unwrap();
+        fetcher.fetch(9).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetched_user_input_is_resolved() {
+        let (url, _) = stub_server("200 OK", "Time: 7\nDistance: 9\n");
+        let dir = temp_dir("fetch-user");
+        let resolver = InputResolver::new(Some(dir.clone()), Some("alice".to_string()));
+        let mut fetcher = Fetcher::new(&url, "s3cr3t", resolver.user_dir()).with_interval(Duration::ZERO);
+
+        let Fetched::Downloaded(path) = fetcher.fetch(6).unwrap() else {
+            panic!("expected a download");
+        };
+        assert_eq!(path, dir.join("alice").join("day06.txt"));
+        assert_eq!(resolver.resolve(6, None).unwrap(), path);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
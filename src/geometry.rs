@@ -0,0 +1,184 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+use itertools::Itertools;
+
+/// A position on a grid, `y` grows downwards like the rows of the inputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    // the neighbouring point in `direction`
+    pub fn step(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from((x, y): (i64, i64)) -> Point {
+        Point::new(x, y)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Point {
+        Point::new(x as i64, y as i64)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    // clockwise, starting from North
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::North => Point::new(0, -1),
+            Direction::East => Point::new(1, 0),
+            Direction::South => Point::new(0, 1),
+            Direction::West => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.turn_right().opposite()
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+}
+
+// Twice the area enclosed by the polygon going through `vertices` in order (shoelace formula),
+// the last vertex is joined back to the first one. Doubling keeps the result an integer.
+pub fn shoelace_double_area(vertices: &[Point]) -> i64 {
+    vertices.iter()
+        .circular_tuple_windows()
+        .map(|(u, v)| u.x * v.y - u.y * v.x)
+        .sum::<i64>()
+        .abs()
+}
+
+// Number of lattice points strictly inside a polygon from its area and the lattice points on its border (Pick's theorem)
+pub fn pick_interior_points(double_area: i64, boundary_points: i64) -> i64 {
+    (double_area - boundary_points) / 2 + 1
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manhattan() {
+        assert_eq!(Point::new(4, 0).manhattan(Point::new(9, 1)), 6);
+        assert_eq!(Point::new(0, 2).manhattan(Point::new(12, 7)), 17);
+        assert_eq!(Point::new(12, 7).manhattan(Point::new(0, 2)), 17);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut point = Point::new(3, -2);
+        assert_eq!(point + Point::new(1, 1), Point::new(4, -1));
+        assert_eq!(point - Point::new(1, 1), Point::new(2, -3));
+        assert_eq!(point * 3, Point::new(9, -6));
+        assert_eq!(-point, Point::new(-3, 2));
+        point += Point::new(-3, 2);
+        assert_eq!(point, Point::ORIGIN);
+        assert_eq!(Point::from((2_usize, 5_usize)), Point::new(2, 5));
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Point::ORIGIN.step(Direction::North), Point::new(0, -1));
+        assert_eq!(Point::ORIGIN.step(Direction::East).step(Direction::South), Point::new(1, 1));
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(direction.offset() + direction.opposite().offset(), Point::ORIGIN);
+            assert_ne!(direction.turn_left(), direction.turn_right());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::East.opposite(), Direction::West);
+    }
+
+    #[test]
+    fn test_shoelace_and_pick() {
+        // every point of the border of a 3x3 square, clockwise
+        let square: Vec<Point> = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1)]
+            .into_iter().map(|(x, y)| Point::new(x, y)).collect();
+        assert_eq!(shoelace_double_area(&square), 8);
+        assert_eq!(pick_interior_points(8, square.len() as i64), 1);
+        let reversed: Vec<Point> = square.into_iter().rev().collect();
+        assert_eq!(shoelace_double_area(&reversed), 8);
+        // only the corners of a triangle
+        assert_eq!(shoelace_double_area(&[Point::new(0, 0), Point::new(4, 0), Point::new(0, 3)]), 12);
+    }
+}
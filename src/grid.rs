@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::error::AocError;
+use crate::helpers;
+
+/// A rectangular grid stored row by row, `(x, y)` positions start at the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+const NEIGHBOURS_4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBOURS_8: [(i64, i64); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "a {}x{} grid needs {} cells", width, height, width * height);
+        Grid { width, height, cells }
+    }
+
+    // `None` when the rows do not all have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Grid::new(width, height, rows.into_iter().flatten().collect()))
+    }
+
+    // parse one cell per character, every line of `input` is a row
+    pub fn parse<F: FnMut(char) -> T>(day: u8, input: &str, mut cell: F) -> Result<Grid<T>, AocError> {
+        let lines = helpers::lines(input);
+        let width = helpers::check_width(day, &lines)?;
+        let cells = lines.iter().flat_map(|line| line.chars()).map(&mut cell).collect::<Vec<T>>();
+        Ok(Grid::new(width, lines.len(), cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> Option<&T> {
+        if self.contains(x, y) {
+            self.cells.get(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        if self.contains(x, y) {
+            self.cells.get_mut(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(x < self.width, "column {} is out of a grid of width {}", x, self.width);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    // every cell with its `(x, y)` position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.cells.iter().enumerate().map(move |(idx, cell)| ((idx % self.width, idx / self.width), cell))
+    }
+
+    pub fn position<P: FnMut(&T) -> bool>(&self, mut predicate: P) -> Option<(usize, usize)> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(position, _)| position)
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    // positions up, right, down and left of `(x, y)` that are inside the grid
+    pub fn neighbours_4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &NEIGHBOURS_4)
+    }
+
+    // same as `neighbours_4` with the diagonals
+    pub fn neighbours_8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &NEIGHBOURS_8)
+    }
+
+    fn neighbours<'a>(&'a self, x: usize, y: usize, offsets: &'a [(i64, i64)]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter()
+            .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(move |&(nx, ny)| self.contains(nx, ny))
+            .map(|(nx, ny)| (nx as usize, ny as usize))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    // rows become columns
+    pub fn transpose(&self) -> Grid<T> {
+        let cells = (0..self.width).flat_map(|x| self.column(x).cloned()).collect();
+        Grid::new(self.height, self.width, cells)
+    }
+
+    // mirror left to right
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let cells = self.rows().flat_map(|row| row.iter().rev().cloned()).collect();
+        Grid::new(self.width, self.height, cells)
+    }
+
+    // mirror top to bottom
+    pub fn flip_vertical(&self) -> Grid<T> {
+        let cells = self.rows().rev().flat_map(|row| row.iter().cloned()).collect();
+        Grid::new(self.width, self.height, cells)
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        self.transpose().flip_horizontal()
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Grid<T> {
+        self.transpose().flip_vertical()
+    }
+}
+
+impl Grid<char> {
+    pub fn lines(&self) -> Vec<String> {
+        self.rows().map(|row| row.iter().collect()).collect()
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse(0, "abc\ndef", |c| c).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(2, 1), Some(&'f'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.position(|&c| c == 'e'), Some((1, 1)));
+        assert!(Grid::parse(0, "abc\nde", |c| c).is_err());
+        assert_eq!(Grid::parse(0, "é.\n..", |c| c).unwrap().row(0), &['é', '.']);
+        assert!(Grid::parse(0, "", |c| c).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_rows() {
+        assert_eq!(Grid::from_rows(vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]), Some(sample()));
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(grid.neighbours_4(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours_4(1, 1).count(), 4);
+        assert_eq!(grid.neighbours_8(1, 1).count(), 8);
+        assert_eq!(grid.neighbours_8(2, 2).collect::<Vec<_>>(), vec![(1, 1), (2, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_get_mut_and_map() {
+        let mut grid = sample();
+        *grid.get_mut(0, 0).unwrap() = 'z';
+        assert_eq!(grid.map(|c| c.is_ascii_lowercase()), Grid::filled(3, 2, true));
+        assert_eq!(grid.iter().next(), Some(((0, 0), &'z')));
+    }
+}
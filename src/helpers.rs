@@ -1,14 +1,79 @@
-use std::io::{BufRead, BufReader, Error, ErrorKind};
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
 use std::result::Result;
-use std::fs::File;
-
-pub fn read(filepath: &str) -> Result<Vec<String>, Error> {
-    let file = match File::open(filepath) {
-        Ok(f) => f,
-        Err(_) => return Err(Error::new(ErrorKind::NotFound,format!("file {} does not exist!", filepath)))
-    };
-    let br = BufReader::new(file);
-    br.lines()
-        .map(|line| line.and_then(|v| v.parse().map_err(|e| Error::new(ErrorKind::InvalidData, e))))
-        .collect()
+
+use nom::IResult;
+
+use crate::error::AocError;
+
+pub fn read_to_string<P: AsRef<Path>>(filepath: P) -> Result<String, Error> {
+    let filepath = filepath.as_ref();
+    fs::read_to_string(filepath).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => Error::new(ErrorKind::NotFound, format!("file {} does not exist!", filepath.display())),
+        _ => e
+    })
+}
+
+pub fn lines(input: &str) -> Vec<String> {
+    input.lines().map(|line| line.to_string()).collect()
+}
+
+// run a nom parser on the line at `line_idx` (0-based) and make sure nothing is left behind
+pub fn parse_all<'a, O, F>(day: u8, line_idx: usize, line: &'a str, mut parser: F) -> Result<O, AocError>
+    where F: FnMut(&'a str) -> IResult<&'a str, O> {
+    let (remaining, res) = parser(line).map_err(|e| AocError::from_nom(day, line, line_idx + 1, e))?;
+    if remaining.is_empty() {
+        Ok(res)
+    } else {
+        Err(AocError::parse(day, line, line_idx + 1, remaining, "unexpected trailing input"))
+    }
+}
+
+// grid-like inputs must have all their lines of the same width, in characters
+pub fn check_width(day: u8, lines: &[String]) -> Result<usize, AocError> {
+    let width = lines.first().map_or(0, |line| line.chars().count());
+    match lines.iter().enumerate().find(|(_, line)| line.chars().count() != width) {
+        Some((idx, line)) => Err(AocError::parse(
+            day,
+            line,
+            idx + 1,
+            &line[line.char_indices().nth(width).map_or(line.len(), |(offset, _)| offset)..],
+            format!("expected {} columns, found {}", width, line.chars().count()),
+        )),
+        None => Ok(width),
+    }
+}
+
+// A fresh empty directory for a test, `name` keeps the tests running in parallel apart
+#[cfg(test)]
+pub(crate) fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-2023-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(parse_all(9, 0, "12", complete::i64).unwrap(), 12);
+        let err = parse_all(9, 3, "12x", complete::i64).unwrap_err();
+        assert_eq!(err.to_string(), "day 09: parse error at line 4, column 3: unexpected trailing input\n    12x\n      ^");
+    }
+
+    #[test]
+    fn test_check_width() {
+        assert_eq!(check_width(14, &lines("..#\n#..")).unwrap(), 3);
+        let err = check_width(14, &lines("..#\n#.\n...")).unwrap_err();
+        assert_eq!(err.to_string(), "day 14: parse error at line 2, column 3: expected 3 columns, found 2\n    #.\n      ^");
+        let err = check_width(10, &lines("é.\n..\n...")).unwrap_err();
+        assert_eq!(err.to_string(), "day 10: parse error at line 3, column 3: expected 2 columns, found 3\n    ...\n      ^");
+    }
 }
@@ -0,0 +1,140 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::error::AocError;
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const USER_VAR: &str = "AOC_USER";
+
+// inputs shipped with the repository, independent of the working directory
+const BUILTIN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/input");
+
+// Find the input files of a day: `$AOC_INPUT_DIR/<user>/<name>.txt`, then `$AOC_INPUT_DIR/<name>.txt`,
+// then the files shipped in `src/input`. Without an input directory the user inputs live in `src/input/<user>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputResolver {
+    dir: Option<PathBuf>,
+    user: Option<String>,
+}
+
+impl InputResolver {
+    pub fn new(dir: Option<PathBuf>, user: Option<String>) -> InputResolver {
+        InputResolver { dir, user }
+    }
+
+    pub fn from_env() -> InputResolver {
+        let non_empty = |var: &str| env::var(var).ok().filter(|value| !value.is_empty());
+        InputResolver::new(non_empty(INPUT_DIR_VAR).map(PathBuf::from), non_empty(USER_VAR))
+    }
+
+    pub fn with_user(self, user: Option<String>) -> InputResolver {
+        InputResolver { user: user.or(self.user), ..self }
+    }
+
+    // the directory personal inputs are stored in
+    pub fn user_dir(&self) -> PathBuf {
+        let dir = self.dir.clone().unwrap_or_else(|| PathBuf::from(BUILTIN_DIR));
+        match &self.user {
+            Some(user) => dir.join(user),
+            None => dir,
+        }
+    }
+
+    pub fn candidates(&self, name: &str) -> Vec<PathBuf> {
+        self.file_candidates(&format!("{}.txt", name))
+    }
+
+    // same lookup order for any other file kept with the inputs, like `answers.toml`
+    pub fn file_candidates(&self, file: &str) -> Vec<PathBuf> {
+        let mut candidates = vec![];
+        if self.user.is_some() {
+            candidates.push(self.user_dir().join(file));
+        }
+        if let Some(dir) = &self.dir {
+            candidates.push(dir.join(file));
+        }
+        candidates.push(Path::new(BUILTIN_DIR).join(file));
+        candidates
+    }
+
+    pub fn resolve(&self, day: u8, variant: Option<&str>) -> Result<PathBuf, AocError> {
+        let name = input_name(day, variant);
+        let candidates = self.candidates(&name);
+        match candidates.iter().find(|path| path.is_file()) {
+            Some(path) => Ok(path.clone()),
+            None => Err(AocError::MissingInput { name, searched: candidates }),
+        }
+    }
+}
+
+// `None` is the puzzle input `dayNN`, a variant without digits gets the day appended (`sample` -> `sample07`)
+pub fn input_name(day: u8, variant: Option<&str>) -> String {
+    match variant {
+        None => format!("day{:02}", day),
+        Some(name) if name.chars().any(|c| c.is_ascii_digit()) => name.to_string(),
+        Some(name) => format!("{}{:02}", name, day),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::helpers::temp_dir;
+
+    use super::*;
+
+    #[test]
+    fn test_input_name() {
+        assert_eq!(input_name(7, None), "day07");
+        assert_eq!(input_name(7, Some("sample")), "sample07");
+        assert_eq!(input_name(7, Some("sample07b")), "sample07b");
+    }
+
+    #[test]
+    fn test_resolve_user_input() {
+        let dir = temp_dir("resolver");
+        fs::create_dir_all(dir.join("alice")).unwrap();
+        fs::write(dir.join("alice").join("day07.txt"), "").unwrap();
+        fs::write(dir.join("day08.txt"), "").unwrap();
+
+        let resolver = InputResolver::new(Some(dir.clone()), Some("alice".to_string()));
+        assert_eq!(resolver.resolve(7, None).unwrap(), dir.join("alice").join("day07.txt"));
+        assert_eq!(resolver.resolve(8, None).unwrap(), dir.join("day08.txt"));
+        assert_eq!(resolver.resolve(7, Some("sample07b")).unwrap(), Path::new(BUILTIN_DIR).join("sample07b.txt"));
+        assert_eq!(resolver.user_dir(), dir.join("alice"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_missing() {
+        let dir = temp_dir("missing");
+        let resolver = InputResolver::new(Some(dir.clone()), Some("bob".to_string()));
+        match resolver.resolve(7, Some("nothing")) {
+            Err(AocError::MissingInput { name, searched }) => {
+                assert_eq!(name, "nothing07");
+                assert_eq!(searched.len(), 3);
+                assert_eq!(searched[0], dir.join("bob").join("nothing07.txt"));
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_missing_without_input_dir() {
+        let resolver = InputResolver::new(None, Some("bob".to_string()));
+        match resolver.resolve(7, Some("nothing")) {
+            Err(AocError::MissingInput { searched, .. }) => {
+                assert_eq!(searched, vec![
+                    Path::new(BUILTIN_DIR).join("bob").join("nothing07.txt"),
+                    Path::new(BUILTIN_DIR).join("nothing07.txt"),
+                ]);
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Advent of Code 2023 solutions.
+//!
+//! Every `dayNN` module exposes `solve`/`solve_v2` taking the puzzle input as text, and a `DayNN`
+//! implementing [`Solution`]. [`REGISTRY`] lists all of them for tools that run days generically.
+
+pub mod answers;
+pub mod bench;
+pub mod cycle;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod error;
+pub mod fetch;
+pub mod geometry;
+pub mod grid;
+pub mod helpers;
+pub mod input;
+pub mod report;
+pub mod solution;
+
+pub use error::AocError;
+pub use solution::{Puzzle, Solution, REGISTRY};
@@ -1,27 +1,410 @@
-use std::time::{Instant};
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod helpers;
-
-fn main() {
-    let time_for_part1 = Instant::now();
-    let part1 = day14::part_1();
-    println!("{}", part1);
-    println!("Done in {}ms", time_for_part1.elapsed().as_millis());
-    let time_for_part2 = Instant::now();
-    let part2 = day14::part_2();
-    println!("Done in {}ms", time_for_part2.elapsed().as_millis());
+use std::io;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use clap::{Parser, Subcommand};
+
+use aoc_2023::answers::{self, Answers, ANSWERS_FILE};
+use aoc_2023::bench::{self, Baseline};
+use aoc_2023::day08;
+use aoc_2023::error::AocError;
+use aoc_2023::fetch::{Fetched, Fetcher};
+use aoc_2023::helpers;
+use aoc_2023::input::{self, InputResolver};
+use aoc_2023::report::{self, Format, Job};
+use aoc_2023::solution::{self, Puzzle, REGISTRY};
+
+const DAYS: u8 = REGISTRY.len() as u8;
+
+#[derive(Parser)]
+#[command(name = "aoc-2023-rust", about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one day (or `all` of them, in parallel) and print the answers with their timing
+    Run {
+        /// Day to run, from 1 to 14, or `all`
+        #[arg(short, long)]
+        day: DaySelector,
+        /// Only run this part (1 or 2), both parts are run by default
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Read the puzzle input from this file instead of resolving `dayNN.txt`
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Read the puzzle input from the standard input
+        #[arg(long, conflicts_with = "input")]
+        stdin: bool,
+        /// Use a named input such as `sample07b`, or `sample` for the first sample of every day
+        #[arg(long, conflicts_with_all = ["input", "stdin"])]
+        variant: Option<String>,
+        /// Look for inputs in `$AOC_INPUT_DIR/<user>` (or `src/input/<user>`) first, defaults to `$AOC_USER`
+        #[arg(short, long)]
+        user: Option<String>,
+        /// Output format: `text`, or `json` for one JSON object per line and part
+        #[arg(short, long, default_value = "text")]
+        format: Format,
+        /// Run the days one after the other instead of in parallel, for cleaner timings
+        #[arg(long)]
+        sequential: bool,
+    },
+    /// Check the answers of every day against the expected answers in `answers.toml`
+    Verify {
+        /// Day to verify, from 1 to 14, or `all`
+        #[arg(short, long, default_value = "all")]
+        day: DaySelector,
+        /// Only verify this part (1 or 2)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Look for inputs and answers in `$AOC_INPUT_DIR/<user>` (or `src/input/<user>`) first, defaults to `$AOC_USER`
+        #[arg(short, long)]
+        user: Option<String>,
+        /// Read the expected answers from this file instead of the `answers.toml` next to the inputs
+        #[arg(short, long)]
+        answers: Option<String>,
+    },
+    /// Time the parsing and solving of each day over several runs
+    Bench {
+        /// Day to benchmark, from 1 to 14, or `all`
+        #[arg(short, long, default_value = "all")]
+        day: DaySelector,
+        /// Only benchmark this part (1 or 2)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Number of timed runs of each part
+        #[arg(short, long, default_value_t = 10, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        runs: usize,
+        /// Number of untimed runs before measuring
+        #[arg(short, long, default_value_t = 1)]
+        warmup: usize,
+        /// Look for inputs in `$AOC_INPUT_DIR/<user>` (or `src/input/<user>`) first, defaults to `$AOC_USER`
+        #[arg(short, long)]
+        user: Option<String>,
+        /// Save the measurements to this JSON file
+        #[arg(long)]
+        save: Option<String>,
+        /// Compare the measurements with a JSON file written by `--save`
+        #[arg(long)]
+        baseline: Option<String>,
+        /// Flag a part as slower when its median time grew by more than this percentage
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Show how a part gets its answer, step by step, for the days that support it (day 7)
+    Explain {
+        /// Day to explain, from 1 to 14
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=DAYS as i64))]
+        day: u8,
+        /// Part to explain (1 or 2)
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Read the puzzle input from this file instead of resolving `dayNN.txt`
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Use a named input such as `sample07b`, or `sample` for the first sample of the day
+        #[arg(long, conflicts_with = "input")]
+        variant: Option<String>,
+        /// Look for inputs in `$AOC_INPUT_DIR/<user>` (or `src/input/<user>`) first, defaults to `$AOC_USER`
+        #[arg(short, long)]
+        user: Option<String>,
+    },
+    /// Print the day 8 network as a Graphviz DOT graph, to render with `dot -Tsvg`
+    Dot {
+        /// Read the puzzle input from this file instead of resolving `day08.txt`
+        #[arg(short, long)]
+        input: Option<String>,
+        /// Use a named input such as `sample08b`, or `sample` for the first sample of the day
+        #[arg(long, conflicts_with = "input")]
+        variant: Option<String>,
+        /// Look for inputs in `$AOC_INPUT_DIR/<user>` (or `src/input/<user>`) first, defaults to `$AOC_USER`
+        #[arg(short, long)]
+        user: Option<String>,
+        /// Colour the path walked from this node
+        #[arg(long)]
+        from: Option<String>,
+    },
+    /// Download puzzle inputs into the input directory, inputs already there are kept as is
+    Fetch {
+        /// Day to download, from 1 to 14, or `all`
+        #[arg(short, long)]
+        day: DaySelector,
+        /// Store the inputs in `$AOC_INPUT_DIR/<user>` (or `src/input/<user>`), defaults to `$AOC_USER`
+        #[arg(short, long)]
+        user: Option<String>,
+        /// Download from this URL instead of `$AOC_BASE_URL` or https://adventofcode.com/2023
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DaySelector {
+    All,
+    Day(u8),
+}
+
+impl FromStr for DaySelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(DaySelector::All);
+        }
+        match s.parse::<u8>() {
+            Ok(day) if (1..=DAYS).contains(&day) => Ok(DaySelector::Day(day)),
+            _ => Err(format!("expected a day between 1 and {} or `all`, got `{}`", DAYS, s)),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Run { day, part, input, stdin, variant, user, format, sequential } => {
+            let puzzles: Vec<&dyn Puzzle> = match day {
+                DaySelector::All => REGISTRY.to_vec(),
+                DaySelector::Day(d) => vec![solution::get(d).unwrap()],
+            };
+            if (input.is_some() || stdin) && puzzles.len() > 1 {
+                eprintln!("--input and --stdin can only be used with a single day");
+                return ExitCode::FAILURE;
+            }
+            if let Some(v) = variant.as_deref().filter(|v| v.chars().any(|c| c.is_ascii_digit())) {
+                if puzzles.len() > 1 {
+                    eprintln!("--variant {} names the input of a single day", v);
+                    return ExitCode::FAILURE;
+                }
+            }
+            let resolver = InputResolver::from_env().with_user(user);
+            let parts: Vec<u8> = part.map_or(vec![1, 2], |p| vec![p]);
+            let jobs: Vec<Job> = puzzles.into_iter().map(|puzzle| {
+                let (label, content) = if stdin {
+                    ("<stdin>".to_string(), io::read_to_string(io::stdin()).map_err(AocError::from))
+                } else {
+                    let filepath = match &input {
+                        Some(filepath) => Ok(filepath.into()),
+                        None => resolver.resolve(puzzle.day(), variant.as_deref()),
+                    };
+                    match filepath {
+                        Ok(filepath) => (filepath.display().to_string(), helpers::read_to_string(&filepath).map_err(AocError::from)),
+                        Err(e) => (input::input_name(puzzle.day(), variant.as_deref()), Err(e)),
+                    }
+                };
+                Job { puzzle, input: label, content }
+            }).collect();
+            let reports = report::run_all(&jobs, &parts, sequential);
+            for r in reports.iter() {
+                match format {
+                    Format::Text => {
+                        if r.part == parts[0] {
+                            println!("Day {:02} - input: {}", r.day, r.input);
+                        }
+                        match r.status {
+                            report::Status::Ok => println!("{}", r),
+                            report::Status::Error => eprintln!("{}", r),
+                        }
+                    }
+                    Format::Json => println!("{}", r.to_json()),
+                }
+            }
+            if reports.iter().any(|r| r.status == report::Status::Error) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Verify { day, part, user, answers } => match verify(day, part, user, answers) {
+            Ok(true) => {}
+            Ok(false) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Bench { day, part, runs, warmup, user, save, baseline, threshold } => {
+            let options = BenchOptions { runs, warmup, save, baseline, threshold };
+            match bench(day, part, user, options) {
+                Ok(true) => {}
+                Ok(false) => return ExitCode::FAILURE,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::Explain { day, part, input, variant, user } => match explain(day, part, input, variant, user) {
+            Ok(true) => {}
+            Ok(false) => return ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Dot { input, variant, user, from } => match dot(input, variant, user, from) {
+            Ok(graph) => print!("{}", graph),
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Fetch { day, user, base_url } => {
+            if let Err(e) = fetch(day, user, base_url) {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn verify(day: DaySelector, part: Option<u8>, user: Option<String>, answers: Option<String>) -> Result<bool, AocError> {
+    let resolver = InputResolver::from_env().with_user(user);
+    let answers_path = match answers {
+        Some(path) => path.into(),
+        None => {
+            let candidates = resolver.file_candidates(ANSWERS_FILE);
+            candidates.iter().find(|path| path.is_file()).cloned()
+                .ok_or(AocError::MissingInput { name: ANSWERS_FILE.to_string(), searched: candidates })?
+        }
+    };
+    println!("Answers: {}", answers_path.display());
+    let answers = Answers::load(&answers_path)?;
+    let puzzles: Vec<&dyn Puzzle> = match day {
+        DaySelector::All => REGISTRY.to_vec(),
+        DaySelector::Day(d) => vec![solution::get(d).unwrap()],
+    };
+    let parts: Vec<u8> = part.map_or(vec![1, 2], |p| vec![p]);
+    let checks = answers::verify(&puzzles, &parts, &answers, &resolver);
+    print!("{}", answers::format_table(&checks));
+    Ok(checks.iter().all(|check| matches!(check.status(), answers::Status::Pass | answers::Status::Missing)))
+}
+
+struct BenchOptions {
+    runs: usize,
+    warmup: usize,
+    save: Option<String>,
+    baseline: Option<String>,
+    threshold: f64,
+}
+
+fn bench(day: DaySelector, part: Option<u8>, user: Option<String>, options: BenchOptions) -> Result<bool, AocError> {
+    let baseline = options.baseline.map(Baseline::load).transpose()?;
+    let resolver = InputResolver::from_env().with_user(user);
+    let puzzles: Vec<&dyn Puzzle> = match day {
+        DaySelector::All => REGISTRY.to_vec(),
+        DaySelector::Day(d) => vec![solution::get(d).unwrap()],
+    };
+    let parts: Vec<u8> = part.map_or(vec![1, 2], |p| vec![p]);
+    let mut measurements = vec![];
+    for puzzle in puzzles {
+        let content = helpers::read_to_string(resolver.resolve(puzzle.day(), None)?)?;
+        for &p in parts.iter() {
+            measurements.push(bench::measure(puzzle, &content, p, options.warmup, options.runs)?);
+        }
+    }
+    print!("{}", bench::format_table(&measurements, baseline.as_ref(), options.threshold / 100.0));
+    if let Some(path) = options.save {
+        Baseline { measurements: measurements.clone() }.save(&path)?;
+        println!("Saved to {}", path);
+    }
+    let slower = baseline.is_some_and(|baseline| {
+        measurements.iter().any(|m| bench::change(m, &baseline).is_some_and(|change| change > options.threshold / 100.0))
+    });
+    Ok(!slower)
+}
+
+fn explain(day: u8, part: u8, input: Option<String>, variant: Option<String>, user: Option<String>) -> Result<bool, AocError> {
+    let puzzle = solution::get(day).unwrap();
+    let filepath = match input {
+        Some(filepath) => filepath.into(),
+        None => InputResolver::from_env().with_user(user).resolve(day, variant.as_deref())?,
+    };
+    match puzzle.explain(&helpers::read_to_string(&filepath)?, part)? {
+        Some(explanation) => {
+            println!("Day {:02} - part {} - input: {}", day, part, filepath.display());
+            print!("{}", explanation);
+            Ok(true)
+        }
+        None => {
+            eprintln!("Day {:02} has no explain mode", day);
+            Ok(false)
+        }
+    }
+}
+
+fn dot(input: Option<String>, variant: Option<String>, user: Option<String>, from: Option<String>) -> Result<String, AocError> {
+    let filepath = match input {
+        Some(filepath) => filepath.into(),
+        None => InputResolver::from_env().with_user(user).resolve(8, variant.as_deref())?,
+    };
+    day08::dot(&helpers::read_to_string(&filepath)?, from.as_deref())
+}
+
+fn fetch(day: DaySelector, user: Option<String>, base_url: Option<String>) -> Result<(), AocError> {
+    let resolver = InputResolver::from_env().with_user(user);
+    let mut fetcher = Fetcher::from_env(resolver.user_dir())?;
+    if let Some(base_url) = base_url {
+        fetcher = fetcher.with_base_url(&base_url);
+    }
+    let days: Vec<u8> = match day {
+        DaySelector::All => (1..=DAYS).collect(),
+        DaySelector::Day(d) => vec![d],
+    };
+    for d in days {
+        match fetcher.fetch(d)? {
+            Fetched::Cached(path) => println!("Day {:02} - already cached in {}", d, path.display()),
+            Fetched::Downloaded(path) => println!("Day {:02} - downloaded to {}", d, path.display()),
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_selector() {
+        assert_eq!("all".parse::<DaySelector>(), Ok(DaySelector::All));
+        assert_eq!("7".parse::<DaySelector>(), Ok(DaySelector::Day(7)));
+        assert!("0".parse::<DaySelector>().is_err());
+        assert!("15".parse::<DaySelector>().is_err());
+        assert!("seven".parse::<DaySelector>().is_err());
+    }
+
+    #[test]
+    fn test_cli_parse() {
+        let cli = Cli::try_parse_from(["aoc-2023-rust", "run", "--day", "7", "--part", "2"]).unwrap();
+        let Command::Run { day, part, input, stdin, variant, user, format, sequential } = cli.command else {
+            panic!("expected the run command");
+        };
+        assert_eq!(day, DaySelector::Day(7));
+        assert_eq!(part, Some(2));
+        assert_eq!(input, None);
+        assert!(!stdin);
+        assert_eq!(variant, None);
+        assert_eq!(user, None);
+        assert_eq!(format, Format::Text);
+        assert!(!sequential);
+        assert!(Cli::try_parse_from(["aoc-2023-rust", "run", "--day", "7", "--part", "3"]).is_err());
+        assert!(Cli::try_parse_from(["aoc-2023-rust", "run", "--day", "7", "--stdin", "--input", "a.txt"]).is_err());
+        assert!(Cli::try_parse_from(["aoc-2023-rust", "run", "--day", "7", "--variant", "sample07b", "--input", "a.txt"]).is_err());
+
+        let cli = Cli::try_parse_from(["aoc-2023-rust", "explain", "--day", "7", "--variant", "sample"]).unwrap();
+        let Command::Explain { day, part, variant, .. } = cli.command else {
+            panic!("expected the explain command");
+        };
+        assert_eq!((day, part, variant.as_deref()), (7, 1, Some("sample")));
+        assert!(Cli::try_parse_from(["aoc-2023-rust", "explain", "--day", "all"]).is_err());
+        assert!(Cli::try_parse_from(["aoc-2023-rust", "bench", "--runs", "0"]).is_err());
+
+        let cli = Cli::try_parse_from(["aoc-2023-rust", "dot", "--variant", "sample08b", "--from", "AAA"]).unwrap();
+        let Command::Dot { variant, from, .. } = cli.command else {
+            panic!("expected the dot command");
+        };
+        assert_eq!((variant.as_deref(), from.as_deref()), (Some("sample08b"), Some("AAA")));
+    }
 }
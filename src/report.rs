@@ -0,0 +1,176 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::error::AocError;
+use crate::solution::Puzzle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("expected `text` or `json`, got `{}`", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Error,
+}
+
+// The outcome of running one part of a day, times are in microseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<i64>,
+    pub parse_us: Option<f64>,
+    pub solve_us: Option<f64>,
+    pub input: String,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Report {
+    pub fn failed(day: u8, part: u8, input: &str, error: &AocError) -> Report {
+        Report {
+            day,
+            part,
+            answer: None,
+            parse_us: None,
+            solve_us: None,
+            input: input.to_string(),
+            status: Status::Error,
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a report is always serializable")
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.answer, &self.error) {
+            (Some(answer), _) => {
+                let elapsed = self.parse_us.unwrap_or(0.0) + self.solve_us.unwrap_or(0.0);
+                writeln!(f, "Day {:02} - part {}: {}", self.day, self.part, answer)?;
+                write!(f, "Done in {}ms", (elapsed / 1000.0) as u128)
+            }
+            (None, error) => {
+                writeln!(f, "Day {:02} - part {} failed", self.day, self.part)?;
+                write!(f, "{}", error.as_deref().unwrap_or("no answer"))
+            }
+        }
+    }
+}
+
+// Run the given parts of a day on `content`, `input` only labels where the content came from
+pub fn run(puzzle: &dyn Puzzle, parts: &[u8], input: &str, content: &Result<String, AocError>) -> Vec<Report> {
+    parts.iter().map(|&part| {
+        let timed = content.as_ref()
+            .map_err(|e| Report::failed(puzzle.day(), part, input, e))
+            .and_then(|content| puzzle.run_timed(content, part).map_err(|e| Report::failed(puzzle.day(), part, input, &e)));
+        match timed {
+            Ok(timed) => Report {
+                day: puzzle.day(),
+                part,
+                answer: Some(timed.answer),
+                parse_us: Some(timed.parse.as_secs_f64() * 1_000_000.0),
+                solve_us: Some(timed.solve.as_secs_f64() * 1_000_000.0),
+                input: input.to_string(),
+                status: Status::Ok,
+                error: None,
+            },
+            Err(report) => report,
+        }
+    }).collect()
+}
+
+// A day to run with its loaded input
+pub struct Job<'a> {
+    pub puzzle: &'a dyn Puzzle,
+    pub input: String,
+    pub content: Result<String, AocError>,
+}
+
+// Run every part of every job, on the rayon pool unless `sequential`, the reports keep the order of the jobs
+pub fn run_all(jobs: &[Job], parts: &[u8], sequential: bool) -> Vec<Report> {
+    let tasks: Vec<(&Job, u8)> = jobs.iter().flat_map(|job| parts.iter().map(move |&part| (job, part))).collect();
+    let run_task = |&(job, part): &(&Job, u8)| run(job.puzzle, &[part], &job.input, &job.content);
+    if sequential {
+        tasks.iter().flat_map(run_task).collect()
+    } else {
+        tasks.par_iter().flat_map_iter(run_task).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::solution;
+
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let content = Ok("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet".to_string());
+        let reports = run(solution::get(1).unwrap(), &[1], "sample01.txt", &content);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].answer, Some(142));
+        assert_eq!(reports[0].status, Status::Ok);
+        assert!(reports[0].to_json().starts_with(r#"{"day":1,"part":1,"answer":142,"parse_us":"#));
+        assert!(reports[0].to_json().ends_with(r#","input":"sample01.txt","status":"ok"}"#));
+    }
+
+    #[test]
+    fn test_failed_input() {
+        let content = Err(AocError::unsolvable(10, "there is no starting position"));
+        let reports = run(solution::get(10).unwrap(), &[1, 2], "<stdin>", &content);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(
+            reports[1].to_json(),
+            r#"{"day":10,"part":2,"answer":null,"parse_us":null,"solve_us":null,"input":"<stdin>","status":"error","error":"day 10: cannot solve this input: there is no starting position"}"#
+        );
+        assert_eq!(reports[0].to_string(), "Day 10 - part 1 failed\nday 10: cannot solve this input: there is no starting position");
+    }
+
+    #[test]
+    fn test_run_all() {
+        let jobs: Vec<Job> = [1, 2, 4].iter().map(|&day| {
+            let input = format!("src/input/sample{:02}.txt", day);
+            let content = std::fs::read_to_string(&input).map_err(AocError::from);
+            Job { puzzle: solution::get(day).unwrap(), input, content }
+        }).collect();
+        let parallel = run_all(&jobs, &[1, 2], false);
+        let order: Vec<(u8, u8)> = parallel.iter().map(|r| (r.day, r.part)).collect();
+        assert_eq!(order, vec![(1, 1), (1, 2), (2, 1), (2, 2), (4, 1), (4, 2)]);
+        let answers: Vec<Option<i64>> = parallel.iter().map(|r| r.answer).collect();
+        assert_eq!(answers, vec![Some(142), Some(142), Some(8), Some(2286), Some(13), Some(30)]);
+
+        let sequential: Vec<Option<i64>> = run_all(&jobs, &[1, 2], true).iter().map(|r| r.answer).collect();
+        assert_eq!(sequential, answers);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
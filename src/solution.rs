@@ -0,0 +1,139 @@
+use std::time::{Duration, Instant};
+
+use crate::error::AocError;
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13, day14};
+
+/// A day of the calendar: the puzzle input is parsed once and shared by both parts.
+pub trait Solution {
+    type Parsed;
+
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError>;
+    fn part_1(&self, parsed: &Self::Parsed) -> Result<i64, AocError>;
+    fn part_2(&self, parsed: &Self::Parsed) -> Result<i64, AocError>;
+
+    /// A readable trace of how a part gets its answer, for the days that have one.
+    fn explain(&self, _parsed: &Self::Parsed, _part: u8) -> Option<String> {
+        None
+    }
+}
+
+/// Object-safe view of a `Solution`, so that days with different `Parsed` types
+/// can be stored in the same registry.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn run(&self, input: &str, part: u8) -> Result<i64, AocError> {
+        self.run_timed(input, part).map(|timed| timed.answer)
+    }
+    fn run_timed(&self, input: &str, part: u8) -> Result<Timed, AocError>;
+    // `None` when the day has no explain mode
+    fn explain(&self, input: &str, part: u8) -> Result<Option<String>, AocError>;
+}
+
+/// An answer with the time spent parsing the input and solving the part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timed {
+    pub answer: i64,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn day(&self) -> u8 {
+        Solution::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Solution::title(self)
+    }
+
+    fn run_timed(&self, input: &str, part: u8) -> Result<Timed, AocError> {
+        check_part(Solution::day(self), part)?;
+        let start = Instant::now();
+        let parsed = self.parse(input)?;
+        let parse = start.elapsed();
+        let start = Instant::now();
+        let answer = match part {
+            1 => self.part_1(&parsed),
+            _ => self.part_2(&parsed),
+        }?;
+        Ok(Timed { answer, parse, solve: start.elapsed() })
+    }
+
+    fn explain(&self, input: &str, part: u8) -> Result<Option<String>, AocError> {
+        check_part(Solution::day(self), part)?;
+        Ok(Solution::explain(self, &self.parse(input)?, part))
+    }
+}
+
+fn check_part(day: u8, part: u8) -> Result<(), AocError> {
+    match part {
+        1 | 2 => Ok(()),
+        _ => Err(AocError::InvalidPart { day, part }),
+    }
+}
+
+pub static REGISTRY: [&dyn Puzzle; 14] = [
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+];
+
+pub fn get(day: u8) -> Option<&'static dyn Puzzle> {
+    REGISTRY.iter().find(|puzzle| puzzle.day() == day).copied()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered() {
+        let days: Vec<u8> = REGISTRY.iter().map(|puzzle| puzzle.day()).collect();
+        assert_eq!(days, (1..=14).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_get() {
+        assert_eq!(get(7).unwrap().title(), "Camel Cards");
+        assert!(get(15).is_none());
+    }
+
+    #[test]
+    fn test_run() {
+        let sample = std::fs::read_to_string("src/input/sample01.txt").unwrap();
+        assert_eq!(get(1).unwrap().run(&sample, 1).unwrap(), 142);
+    }
+
+    #[test]
+    fn test_run_invalid_part() {
+        let sample = std::fs::read_to_string("src/input/sample01.txt").unwrap();
+        for part in [0, 3] {
+            let err = REGISTRY[0].run(&sample, part).unwrap_err();
+            assert_eq!(err.to_string(), format!("day 01: there is no part {}, only parts 1 and 2", part));
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        assert_eq!(get(1).unwrap().explain("1abc2", 1).unwrap(), None);
+        assert!(get(7).unwrap().explain("32T3K 765", 2).unwrap().is_some());
+        assert!(get(7).unwrap().explain("32T3X 765", 2).is_err());
+        let err = get(7).unwrap().explain("32T3K 765", 3).unwrap_err();
+        assert_eq!(err.to_string(), "day 07: there is no part 3, only parts 1 and 2");
+    }
+}
@@ -0,0 +1,22 @@
+use aoc_2023::{day05, day09, AocError, Solution, REGISTRY};
+
+#[test]
+fn test_solve_from_text() {
+    let sample = std::fs::read_to_string("src/input/sample05.txt").unwrap();
+    assert_eq!(day05::solve(&sample).unwrap(), 35);
+    assert_eq!(day05::solve_v2(&sample).unwrap(), 46);
+}
+
+#[test]
+fn test_solution_trait() {
+    let parsed = day09::Day09.parse("0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45").unwrap();
+    assert_eq!(day09::Day09.part_1(&parsed).unwrap(), 114);
+    assert_eq!(day09::Day09.part_2(&parsed).unwrap(), 2);
+}
+
+#[test]
+fn test_registry() {
+    assert_eq!(REGISTRY.len(), 14);
+    let err = REGISTRY[9].run("", 1).unwrap_err();
+    assert!(matches!(err, AocError::Unsolvable { day: 10, .. }), "{}", err);
+}